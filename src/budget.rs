@@ -0,0 +1,446 @@
+use crate::{Ck3Error, Ck3ErrorKind};
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// How many tokens may be spent between deadline checks
+const DEADLINE_INTERVAL: u64 = 4096;
+
+/// Bounds the work spent on a save, so that hostile input can't tie up a
+/// service indefinitely. Once exhausted, operations return
+/// [`Ck3ErrorKind::BudgetExceeded`].
+///
+/// Clones of a budget draw from the same counter, so the budget given to
+/// [`Ck3File::parse_with_budget`](crate::Ck3File::parse_with_budget) covers
+/// both the parsing and the subsequent deserialization of the file.
+#[derive(Debug, Clone, Default)]
+pub struct TokenBudget {
+    max_tokens: Option<u64>,
+    deadline: Option<Instant>,
+    spent: Arc<AtomicU64>,
+}
+
+impl TokenBudget {
+    /// Creates a budget without any limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of tokens that may be processed. Parsing spends a
+    /// token for each token in the document and deserialization spends a
+    /// token for each value visited.
+    pub fn max_tokens(self, max_tokens: u64) -> Self {
+        TokenBudget {
+            max_tokens: Some(max_tokens),
+            ..self
+        }
+    }
+
+    /// Abort once the deadline has passed. The deadline is checked
+    /// periodically, so an operation may run slightly past it.
+    pub fn deadline(self, deadline: Instant) -> Self {
+        TokenBudget {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// The number of tokens spent so far
+    pub fn spent(&self) -> u64 {
+        self.spent.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.deadline.is_none()
+    }
+
+    pub(crate) fn spend(&self, tokens: u64) -> Result<(), Ck3Error> {
+        if self.is_unlimited() {
+            return Ok(());
+        }
+
+        let before = self.spent.fetch_add(tokens, Ordering::Relaxed);
+        let spent = before + tokens;
+        let over_tokens = self.max_tokens.is_some_and(|max| spent > max);
+        let check_deadline = before == 0 || before / DEADLINE_INTERVAL != spent / DEADLINE_INTERVAL;
+        if over_tokens || (check_deadline && self.past_deadline()) {
+            return Err(Ck3ErrorKind::BudgetExceeded.into());
+        }
+
+        Ok(())
+    }
+
+    fn past_deadline(&self) -> bool {
+        self.deadline.is_some_and(|x| Instant::now() >= x)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.max_tokens.is_some_and(|max| self.spent() > max) || self.past_deadline()
+    }
+
+    /// Spends a token for each token in the given document. Malformed
+    /// documents are left for the parser to report.
+    pub(crate) fn spend_document(&self, data: &[u8], binary: bool) -> Result<(), Ck3Error> {
+        if self.is_unlimited() {
+            return Ok(());
+        }
+
+        if binary {
+            let mut reader = jomini::binary::TokenReader::new(data);
+            while let Ok(Some(_)) = reader.next() {
+                self.spend(1)?;
+            }
+        } else {
+            let mut reader = jomini::text::TokenReader::new(data);
+            while let Ok(Some(_)) = reader.next() {
+                self.spend(1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Translates an error raised during budgeted deserialization
+    pub(crate) fn translate<E>(&self, err: E, f: impl FnOnce(E) -> Ck3Error) -> Ck3Error {
+        if self.is_exhausted() {
+            Ck3ErrorKind::BudgetExceeded.into()
+        } else {
+            f(err)
+        }
+    }
+
+    fn spend_de<E: de::Error>(&self) -> Result<(), E> {
+        self.spend(1).map_err(|e| E::custom(e))
+    }
+}
+
+/// A deserializer that spends a token from the budget for every value
+/// deserialized
+pub(crate) struct Budgeted<'b, D> {
+    de: D,
+    budget: &'b TokenBudget,
+}
+
+impl<'b, D> Budgeted<'b, D> {
+    pub(crate) fn new(de: D, budget: &'b TokenBudget) -> Self {
+        Budgeted { de, budget }
+    }
+}
+
+macro_rules! budgeted_deserialize {
+    ($method:ident $(, $arg:ident: $ty:ty)*) => {
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.budget.spend_de()?;
+            let visitor = BudgetedVisitor {
+                visitor,
+                budget: self.budget,
+            };
+            self.de.$method($($arg,)* visitor)
+        }
+    };
+}
+
+impl<'de, D> Deserializer<'de> for Budgeted<'_, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    budgeted_deserialize!(deserialize_any);
+    budgeted_deserialize!(deserialize_bool);
+    budgeted_deserialize!(deserialize_i8);
+    budgeted_deserialize!(deserialize_i16);
+    budgeted_deserialize!(deserialize_i32);
+    budgeted_deserialize!(deserialize_i64);
+    budgeted_deserialize!(deserialize_u8);
+    budgeted_deserialize!(deserialize_u16);
+    budgeted_deserialize!(deserialize_u32);
+    budgeted_deserialize!(deserialize_u64);
+    budgeted_deserialize!(deserialize_f32);
+    budgeted_deserialize!(deserialize_f64);
+    budgeted_deserialize!(deserialize_char);
+    budgeted_deserialize!(deserialize_str);
+    budgeted_deserialize!(deserialize_string);
+    budgeted_deserialize!(deserialize_bytes);
+    budgeted_deserialize!(deserialize_byte_buf);
+    budgeted_deserialize!(deserialize_option);
+    budgeted_deserialize!(deserialize_unit);
+    budgeted_deserialize!(deserialize_unit_struct, name: &'static str);
+    budgeted_deserialize!(deserialize_newtype_struct, name: &'static str);
+    budgeted_deserialize!(deserialize_seq);
+    budgeted_deserialize!(deserialize_tuple, len: usize);
+    budgeted_deserialize!(deserialize_tuple_struct, name: &'static str, len: usize);
+    budgeted_deserialize!(deserialize_map);
+    budgeted_deserialize!(deserialize_struct, name: &'static str, fields: &'static [&'static str]);
+    budgeted_deserialize!(deserialize_enum, name: &'static str, variants: &'static [&'static str]);
+    budgeted_deserialize!(deserialize_identifier);
+    budgeted_deserialize!(deserialize_ignored_any);
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct BudgetedSeed<'b, S> {
+    seed: S,
+    budget: &'b TokenBudget,
+}
+
+impl<'de, S> DeserializeSeed<'de> for BudgetedSeed<'_, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed
+            .deserialize(Budgeted::new(deserializer, self.budget))
+    }
+}
+
+struct BudgetedVisitor<'b, V> {
+    visitor: V,
+    budget: &'b TokenBudget,
+}
+
+macro_rules! forward_visit {
+    ($method:ident, $ty:ty) => {
+        fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            self.visitor.$method(v)
+        }
+    };
+}
+
+impl<'de, V> Visitor<'de> for BudgetedVisitor<'_, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_visit!(visit_bool, bool);
+    forward_visit!(visit_i8, i8);
+    forward_visit!(visit_i16, i16);
+    forward_visit!(visit_i32, i32);
+    forward_visit!(visit_i64, i64);
+    forward_visit!(visit_i128, i128);
+    forward_visit!(visit_u8, u8);
+    forward_visit!(visit_u16, u16);
+    forward_visit!(visit_u32, u32);
+    forward_visit!(visit_u64, u64);
+    forward_visit!(visit_u128, u128);
+    forward_visit!(visit_f32, f32);
+    forward_visit!(visit_f64, f64);
+    forward_visit!(visit_char, char);
+    forward_visit!(visit_str, &str);
+    forward_visit!(visit_borrowed_str, &'de str);
+    forward_visit!(visit_string, String);
+    forward_visit!(visit_bytes, &[u8]);
+    forward_visit!(visit_borrowed_bytes, &'de [u8]);
+    forward_visit!(visit_byte_buf, Vec<u8>);
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor
+            .visit_some(Budgeted::new(deserializer, self.budget))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor
+            .visit_newtype_struct(Budgeted::new(deserializer, self.budget))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(BudgetedAccess {
+            access: seq,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.visitor.visit_map(BudgetedAccess {
+            access: map,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.visitor.visit_enum(BudgetedAccess {
+            access: data,
+            budget: self.budget,
+        })
+    }
+}
+
+/// Wraps the seq, map, enum, and variant accesses so that their elements
+/// are budgeted too
+struct BudgetedAccess<'b, A> {
+    access: A,
+    budget: &'b TokenBudget,
+}
+
+impl<'b, A> BudgetedAccess<'b, A> {
+    fn seed<S>(&self, seed: S) -> BudgetedSeed<'b, S> {
+        BudgetedSeed {
+            seed,
+            budget: self.budget,
+        }
+    }
+}
+
+impl<'de, A> de::SeqAccess<'de> for BudgetedAccess<'_, A>
+where
+    A: de::SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.access.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+impl<'de, A> de::MapAccess<'de> for BudgetedAccess<'_, A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.access.next_key_seed(seed)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.access.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+impl<'de, 'b, A> de::EnumAccess<'de> for BudgetedAccess<'b, A>
+where
+    A: de::EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = BudgetedAccess<'b, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        let (value, access) = self.access.variant_seed(seed)?;
+        let variant = BudgetedAccess {
+            access,
+            budget: self.budget,
+        };
+        Ok((value, variant))
+    }
+}
+
+impl<'de, A> de::VariantAccess<'de> for BudgetedAccess<'_, A>
+where
+    A: de::VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.access.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let seed = self.seed(seed);
+        self.access.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = BudgetedVisitor {
+            visitor,
+            budget: self.budget,
+        };
+        self.access.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let visitor = BudgetedVisitor {
+            visitor,
+            budget: self.budget,
+        };
+        self.access.struct_variant(fields, visitor)
+    }
+}
@@ -1,2 +1,376 @@
 pub use jomini::common::Date as Ck3Date;
 pub use jomini::common::PdsDate;
+
+/// The number of days in each month of the CK3 calendar, which has no leap
+/// years
+const DAYS_PER_MONTH: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Decode an integer that is known to be a binary date, as the melter does
+/// for fields like `birth`
+///
+/// ```
+/// use ck3save::{decode_date, PdsDate};
+/// let date = decode_date(56379360).unwrap();
+/// assert_eq!(date.iso_8601().to_string(), "1436-01-01");
+/// ```
+pub fn decode_date(s: i32) -> Option<Ck3Date> {
+    Ck3Date::from_binary(s)
+}
+
+/// Decode an integer as a binary date if it is likely to be one, as the
+/// melter does for integers of fields that aren't known to be dates
+///
+/// ```
+/// use ck3save::{decode_date_heuristic, PdsDate};
+/// let date = decode_date_heuristic(56379360).unwrap();
+/// assert_eq!(date.iso_8601().to_string(), "1436-01-01");
+/// assert_eq!(decode_date_heuristic(1000), None);
+/// ```
+pub fn decode_date_heuristic(s: i32) -> Option<Ck3Date> {
+    Ck3Date::from_binary_heuristic(s)
+}
+
+/// Calendar arithmetic on [`Ck3Date`] in whole months and years
+pub trait Ck3DateExt {
+    /// Move the date by a number of months (backwards if negative). If the
+    /// day doesn't exist in the resulting month, it is clamped to the last
+    /// day of the month (eg: Jan 31 + 1 month is Feb 28).
+    fn add_months(self, months: i32) -> Ck3Date;
+
+    /// Move the date by a number of years (backwards if negative). Feb 29
+    /// does not exist in the CK3 calendar, so no clamping is necessary.
+    fn add_years(self, years: i32) -> Ck3Date;
+
+    /// Iterate over every day from this date (inclusive) until the end date
+    /// (exclusive). Nothing is yielded if the end is not after this date.
+    fn iter_until(self, end: Ck3Date) -> Ck3DateRange;
+
+    /// Decode a binary date along with its hour component (0 to 23), which
+    /// [`Ck3Date::from_binary`] discards
+    fn from_binary_with_hour(s: i32) -> Option<(Ck3Date, u8)>;
+
+    /// Encode the date with an hour component (0 to 23) into its binary
+    /// representation. The inverse of [`Ck3DateExt::from_binary_with_hour`].
+    fn to_binary_with_hour(self, hour: u8) -> i32;
+
+    /// Decode a binary date that has been written as a 64 bit integer. The
+    /// encoding is the same as [`Ck3Date::from_binary`], so only values
+    /// within the `i32` range are accepted and anything larger returns
+    /// `None`, as it would place the year beyond what a [`Ck3Date`] can
+    /// represent.
+    fn from_binary_i64(s: i64) -> Option<Ck3Date>;
+
+    /// The day of the week. As the CK3 calendar has a fixed 365 day year,
+    /// weekdays are derived from the number of days elapsed since an epoch
+    /// of 1.1.1, which is anchored to be a Monday.
+    fn weekday(&self) -> Weekday;
+
+    /// Convert to a date on the proleptic Gregorian calendar with the same
+    /// year, month, and day. As the CK3 calendar has no leap years, every
+    /// CK3 date has a counterpart, but the number of days between two
+    /// converted dates may differ from [`Ck3Date::days_until`].
+    #[cfg(feature = "chrono")]
+    fn to_naive_date(&self) -> chrono::NaiveDate;
+
+    /// Convert from a date on the proleptic Gregorian calendar with the same
+    /// year, month, and day. Returns `None` for Feb 29 (which doesn't exist
+    /// in the CK3 calendar) and for years outside the range of a CK3 date.
+    #[cfg(feature = "chrono")]
+    fn try_from_naive_date(date: chrono::NaiveDate) -> Option<Ck3Date>;
+}
+
+/// A [`Ck3Date`] that serializes in the game's format (eg: `1066.9.15`)
+/// instead of ISO-8601. Deserializes from either format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ck3DateGameFmt(pub Ck3Date);
+
+impl From<Ck3Date> for Ck3DateGameFmt {
+    fn from(date: Ck3Date) -> Self {
+        Ck3DateGameFmt(date)
+    }
+}
+
+impl From<Ck3DateGameFmt> for Ck3Date {
+    fn from(date: Ck3DateGameFmt) -> Self {
+        date.0
+    }
+}
+
+impl serde::Serialize for Ck3DateGameFmt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&self.0.game_fmt())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Ck3DateGameFmt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct GameFmtVisitor;
+
+        impl serde::de::Visitor<'_> for GameFmtVisitor {
+            type Value = Ck3DateGameFmt;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a date in the game or ISO-8601 format")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ck3Date::parse(v)
+                    .ok()
+                    .or_else(|| parse_iso_8601(v))
+                    .map(Ck3DateGameFmt)
+                    .ok_or_else(|| E::custom(format!("invalid date: {}", v)))
+            }
+        }
+
+        deserializer.deserialize_str(GameFmtVisitor)
+    }
+}
+
+/// Parses the `YYYY-MM-DD` output of [`PdsDate::iso_8601`]
+fn parse_iso_8601(s: &str) -> Option<Ck3Date> {
+    let (sign, rest) = match s.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, s),
+    };
+
+    let mut parts = rest.splitn(3, '-');
+    let year = parts.next()?.parse::<i16>().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?;
+    let day = parts.next()?.parse::<u8>().ok()?;
+    Ck3Date::from_ymd_opt(sign * year, month, day)
+}
+
+/// A day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// An iterator over consecutive days
+///
+/// Created with [`Ck3DateExt::iter_until`]
+#[derive(Debug, Clone)]
+pub struct Ck3DateRange {
+    next: Ck3Date,
+    remaining: i32,
+}
+
+impl Iterator for Ck3DateRange {
+    type Item = Ck3Date;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining <= 0 {
+            return None;
+        }
+
+        let result = self.next;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.next = self.next.add_days(1);
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.remaining).unwrap_or(0);
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Ck3DateRange {}
+
+impl Ck3DateExt for Ck3Date {
+    fn add_months(self, months: i32) -> Ck3Date {
+        let total = i32::from(self.year()) * 12 + i32::from(self.month()) - 1 + months;
+        let year = total.div_euclid(12);
+        let year = year.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16;
+        let month = total.rem_euclid(12) as u8 + 1;
+        let day = self.day().min(DAYS_PER_MONTH[usize::from(month - 1)]);
+        Ck3Date::from_ymd(year, month, day)
+    }
+
+    fn add_years(self, years: i32) -> Ck3Date {
+        self.add_months(years.saturating_mul(12))
+    }
+
+    fn iter_until(self, end: Ck3Date) -> Ck3DateRange {
+        Ck3DateRange {
+            next: self,
+            remaining: self.days_until(&end).max(0),
+        }
+    }
+
+    fn from_binary_with_hour(s: i32) -> Option<(Ck3Date, u8)> {
+        let date = Ck3Date::from_binary(s)?;
+        Some((date, s.rem_euclid(24) as u8))
+    }
+
+    fn to_binary_with_hour(self, hour: u8) -> i32 {
+        self.to_binary() + i32::from(hour.min(23))
+    }
+
+    fn from_binary_i64(s: i64) -> Option<Ck3Date> {
+        i32::try_from(s).ok().and_then(Ck3Date::from_binary)
+    }
+
+    fn weekday(&self) -> Weekday {
+        let epoch = Ck3Date::from_ymd(1, 1, 1);
+        match epoch.days_until(self).rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    fn to_naive_date(&self) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(
+            i32::from(self.year()),
+            u32::from(self.month()),
+            u32::from(self.day()),
+        )
+        .expect("ck3 dates to be valid gregorian dates")
+    }
+
+    #[cfg(feature = "chrono")]
+    fn try_from_naive_date(date: chrono::NaiveDate) -> Option<Ck3Date> {
+        use chrono::Datelike;
+        let year = i16::try_from(date.year()).ok()?;
+        Ck3Date::from_ymd_opt(year, date.month() as u8, date.day() as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_months_wraps_years() {
+        let date = Ck3Date::from_ymd(1066, 11, 15);
+        assert_eq!(date.add_months(2), Ck3Date::from_ymd(1067, 1, 15));
+        assert_eq!(date.add_months(14), Ck3Date::from_ymd(1068, 1, 15));
+        assert_eq!(date.add_months(-11), Ck3Date::from_ymd(1065, 12, 15));
+        assert_eq!(date.add_months(0), date);
+    }
+
+    #[test]
+    fn test_add_months_clamps_day() {
+        let date = Ck3Date::from_ymd(1066, 1, 31);
+        assert_eq!(date.add_months(1), Ck3Date::from_ymd(1066, 2, 28));
+        assert_eq!(date.add_months(3), Ck3Date::from_ymd(1066, 4, 30));
+        assert_eq!(date.add_months(-2), Ck3Date::from_ymd(1065, 11, 30));
+    }
+
+    #[test]
+    fn test_add_years() {
+        let date = Ck3Date::from_ymd(867, 1, 1);
+        assert_eq!(date.add_years(200), Ck3Date::from_ymd(1067, 1, 1));
+        assert_eq!(date.add_years(-67), Ck3Date::from_ymd(800, 1, 1));
+    }
+
+    #[test]
+    fn test_iter_until() {
+        let start = Ck3Date::from_ymd(1066, 12, 30);
+        let dates = start
+            .iter_until(Ck3Date::from_ymd(1067, 1, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                Ck3Date::from_ymd(1066, 12, 30),
+                Ck3Date::from_ymd(1066, 12, 31),
+                Ck3Date::from_ymd(1067, 1, 1),
+            ]
+        );
+
+        assert_eq!(start.iter_until(start).count(), 0);
+        assert_eq!(start.iter_until(Ck3Date::from_ymd(1066, 1, 1)).count(), 0);
+    }
+
+    #[test]
+    fn test_iter_until_multiple_years() {
+        let start = Ck3Date::from_ymd(1066, 3, 1);
+        let end = Ck3Date::from_ymd(1070, 3, 1);
+        let range = start.iter_until(end);
+        assert_eq!(range.len(), 365 * 4);
+        assert_eq!(range.last(), Some(Ck3Date::from_ymd(1070, 2, 28)));
+    }
+
+    #[test]
+    fn test_binary_date_hour() {
+        let date = Ck3Date::from_ymd(867, 1, 1);
+        let binary = date.to_binary() + 5;
+        assert_eq!(Ck3Date::from_binary(binary), Some(date));
+        assert_eq!(Ck3Date::from_binary_with_hour(binary), Some((date, 5)));
+        assert_eq!(date.to_binary_with_hour(5), binary);
+
+        let (date, hour) = Ck3Date::from_binary_with_hour(date.to_binary()).unwrap();
+        assert_eq!(date, Ck3Date::from_ymd(867, 1, 1));
+        assert_eq!(hour, 0);
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(Ck3Date::from_ymd(1, 1, 1).weekday(), Weekday::Monday);
+        assert_eq!(Ck3Date::from_ymd(1, 1, 2).weekday(), Weekday::Tuesday);
+        assert_eq!(Ck3Date::from_ymd(1, 1, 8).weekday(), Weekday::Monday);
+        assert_eq!(Ck3Date::from_ymd(867, 1, 1).weekday(), Weekday::Saturday);
+        assert_eq!(Ck3Date::from_ymd(1066, 9, 15).weekday(), Weekday::Sunday);
+    }
+
+    #[test]
+    fn test_game_fmt_serialize() {
+        let date = Ck3DateGameFmt(Ck3Date::from_ymd(1066, 9, 15));
+        let json = serde_json::to_string(&date).unwrap();
+        assert_eq!(json, r#""1066.9.15""#);
+        let iso = serde_json::to_string(&date.0).unwrap();
+        assert_eq!(iso, r#""1066-09-15""#);
+
+        let from_game: Ck3DateGameFmt = serde_json::from_str(&json).unwrap();
+        let from_iso: Ck3DateGameFmt = serde_json::from_str(&iso).unwrap();
+        assert_eq!(from_game, date);
+        assert_eq!(from_iso, date);
+        assert!(serde_json::from_str::<Ck3DateGameFmt>(r#""1066-13-15""#).is_err());
+    }
+
+    #[test]
+    fn test_from_binary_i64() {
+        let date = Ck3Date::from_ymd(867, 1, 1);
+        let binary = i64::from(date.to_binary());
+        assert_eq!(Ck3Date::from_binary_i64(binary), Some(date));
+        assert_eq!(Ck3Date::from_binary_i64(i64::from(i32::MAX) + 24), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_naive_date_roundtrip() {
+        let date = Ck3Date::from_ymd(1066, 9, 15);
+        let naive = date.to_naive_date();
+        assert_eq!(naive, chrono::NaiveDate::from_ymd_opt(1066, 9, 15).unwrap());
+        assert_eq!(Ck3Date::try_from_naive_date(naive), Some(date));
+
+        let feb_end = Ck3Date::from_ymd(1068, 2, 28);
+        let next = feb_end.to_naive_date().succ_opt().unwrap();
+        assert_eq!(next, chrono::NaiveDate::from_ymd_opt(1068, 2, 29).unwrap());
+        assert_eq!(Ck3Date::try_from_naive_date(next), None);
+    }
+}
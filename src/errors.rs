@@ -49,12 +49,25 @@ pub enum Ck3ErrorKind {
     #[error("error while writing output: {0}")]
     Writer(#[source] jomini::Error),
 
-    #[error("unknown binary token encountered: {token_id:#x}")]
-    UnknownToken { token_id: u16 },
+    #[error(
+        "unknown binary token encountered: {token_id:#x}{}",
+        .offset.map(|x| format!(" at offset: {}", x)).unwrap_or_default()
+    )]
+    #[non_exhaustive]
+    UnknownToken {
+        token_id: u16,
+        /// The byte offset of the token within the (inflated) binary data.
+        /// Only melting reports the offset, it is always `None` when
+        /// deserializing.
+        offset: Option<usize>,
+    },
 
     #[error("invalid header")]
     InvalidHeader,
 
+    #[error("exceeded the allotted token budget or deadline")]
+    BudgetExceeded,
+
     #[error("expected the binary integer: {0} to be parsed as a date")]
     InvalidDate(i32),
 
@@ -88,7 +101,10 @@ impl From<jomini::Error> for Ck3Error {
             match value.into_kind() {
                 jomini::ErrorKind::Deserialize(x) => match x.kind() {
                     &jomini::DeserializeErrorKind::UnknownToken { token_id } => {
-                        Ck3ErrorKind::UnknownToken { token_id }
+                        Ck3ErrorKind::UnknownToken {
+                            token_id,
+                            offset: None,
+                        }
                     }
                     _ => Ck3ErrorKind::Deserialize(x.into()),
                 },
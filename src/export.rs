@@ -0,0 +1,64 @@
+use crate::{models::LivingCharacter, Ck3Error, Ck3File, PdsDate};
+use jomini::binary::TokenResolver;
+use serde::Deserialize;
+use std::{collections::HashMap, io::Write};
+
+/// The subset of the gamestate needed to export characters
+#[derive(Debug, Deserialize)]
+struct Characters {
+    living: HashMap<u64, LivingCharacter>,
+}
+
+/// Write the living characters of a save as CSV with the columns: id,
+/// first_name, gold, health, culture, and birth. Characters are ordered by id
+/// and absent values are left empty.
+pub fn characters_to_csv<R, W>(file: &Ck3File, resolver: &R, mut writer: W) -> Result<(), Ck3Error>
+where
+    R: TokenResolver,
+    W: Write,
+{
+    let mut zip_sink = Vec::new();
+    let parsed = file.parse(&mut zip_sink)?;
+    let characters: Characters = parsed.deserializer(resolver).deserialize()?;
+
+    let mut living = characters.living.into_iter().collect::<Vec<_>>();
+    living.sort_unstable_by_key(|(id, _)| *id);
+
+    writeln!(writer, "id,first_name,gold,health,culture,birth")?;
+    for (id, character) in &living {
+        let alive = character.alive_data.as_ref();
+        write!(writer, "{},", id)?;
+        if let Some(name) = &character.first_name {
+            write_csv_field(&mut writer, name)?;
+        }
+        writer.write_all(b",")?;
+        if let Some(gold) = alive.and_then(|x| x.gold) {
+            write!(writer, "{}", gold)?;
+        }
+        writer.write_all(b",")?;
+        if let Some(health) = alive.and_then(|x| x.health) {
+            write!(writer, "{}", health)?;
+        }
+        writer.write_all(b",")?;
+        if let Some(culture) = character.culture {
+            write!(writer, "{}", culture)?;
+        }
+        writer.write_all(b",")?;
+        if let Some(birth) = character.birth {
+            write!(writer, "{}", birth.iso_8601())?;
+        }
+        writer.write_all(b"\n")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a field, quoting it if it contains a delimiter, quote, or newline
+fn write_csv_field<W: Write>(mut writer: W, field: &str) -> std::io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(writer, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        writer.write_all(field.as_bytes())
+    }
+}
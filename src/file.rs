@@ -1,14 +1,22 @@
 use crate::{
-    flavor::{flavor_from_tape, Ck3BinaryFlavor},
-    Ck3Error, Ck3ErrorKind, Ck3Melter, Encoding, SaveHeader,
+    budget::Budgeted,
+    flavor::{flavor_from_tape, Ck3BinaryFlavor, Ck3FlavorKind},
+    models::HeaderOwned,
+    Ck3Error, Ck3ErrorKind, Ck3Melter, DateAnomaly, Encoding, GoldRepr, MeltLines, MeltOptions,
+    MeltedDocument, SaveHeader, SaveStatistics, TokenBudget,
 };
 use jomini::{
     binary::{FailedResolveStrategy, TokenResolver},
     text::ObjectReader,
-    BinaryDeserializer, BinaryTape, TextDeserializer, TextTape, Utf8Encoding,
+    BinaryDeserializer, BinaryTape, BinaryToken, TextDeserializer, TextTape, Utf8Encoding,
+};
+use serde::{de::DeserializeSeed, Deserialize};
+use std::{
+    collections::HashSet,
+    io::{Cursor, Read},
+    ops::Range,
+    time::Instant,
 };
-use serde::Deserialize;
-use std::io::Cursor;
 use zip::result::ZipError;
 
 #[derive(Clone, Debug)]
@@ -30,14 +38,15 @@ enum FileKind<'a> {
 /// Only consumes enough data to determine encoding of the file
 pub struct Ck3File<'a> {
     header: SaveHeader,
+    save_id_line: &'a [u8],
     kind: FileKind<'a>,
 }
 
 impl<'a> Ck3File<'a> {
     /// Creates a CK3 file from a slice of data
-    pub fn from_slice(data: &[u8]) -> Result<Ck3File, Ck3Error> {
+    pub fn from_slice(data: &[u8]) -> Result<Ck3File<'_>, Ck3Error> {
         let header = SaveHeader::from_slice(data)?;
-        let data = &data[header.header_len()..];
+        let (save_id_line, data) = data.split_at(header.header_len());
 
         let reader = Cursor::new(data);
         match zip::ZipArchive::new(reader) {
@@ -60,6 +69,7 @@ impl<'a> Ck3File<'a> {
                 let is_text = !header.kind().is_binary();
                 Ok(Ck3File {
                     header,
+                    save_id_line,
                     kind: FileKind::Zip(Ck3Zip {
                         archive: files,
                         gamestate: gamestate_idx,
@@ -72,11 +82,13 @@ impl<'a> Ck3File<'a> {
                 if header.kind().is_binary() {
                     Ok(Ck3File {
                         header,
+                        save_id_line,
                         kind: FileKind::Binary(data),
                     })
                 } else {
                     Ok(Ck3File {
                         header,
+                        save_id_line,
                         kind: FileKind::Text(data),
                     })
                 }
@@ -90,6 +102,12 @@ impl<'a> Ck3File<'a> {
         &self.header
     }
 
+    /// Returns the raw bytes of the save id line (eg: `SAV0102...`) exactly as
+    /// they appear in the file, including the line terminator
+    pub fn save_id_line(&self) -> &'a [u8] {
+        self.save_id_line
+    }
+
     /// Returns the detected decoding of the file
     pub fn encoding(&self) -> Encoding {
         match &self.kind {
@@ -100,6 +118,23 @@ impl<'a> Ck3File<'a> {
         }
     }
 
+    /// Returns the binary flavor the save is encoded with, which determines
+    /// how floats are decoded. Plaintext saves have no flavor.
+    pub fn flavor(&self) -> Result<Option<Ck3FlavorKind>, Ck3Error> {
+        match self.gamestate_reader() {
+            (reader, true) => Ok(Some(crate::flavor::flavor_kind_from_reader(reader)?)),
+            (_, false) => Ok(None),
+        }
+    }
+
+    /// Returns true if the save is binary encoded and so needs a
+    /// [`TokenResolver`] populated with the binary tokens to be parsed or
+    /// melted. Check this to fail fast instead of producing output littered
+    /// with unknown tokens.
+    pub fn requires_resolver(&self) -> bool {
+        matches!(self.encoding(), Encoding::Binary | Encoding::BinaryZip)
+    }
+
     /// Returns the size of the file
     ///
     /// The size includes the inflated size of the zip
@@ -110,6 +145,25 @@ impl<'a> Ck3File<'a> {
         }
     }
 
+    /// Returns the byte offsets of the gamestate within the data the file
+    /// was created from, so that an edited gamestate can be spliced back in.
+    ///
+    /// For uncompressed saves, the range covers the metadata and gamestate
+    /// that follow the save id line. For zip files, the range refers to the
+    /// compressed bytes of the gamestate entry within the archive, so these
+    /// bytes need to be inflated to be read, and a spliced in replacement
+    /// must be compressed the same way (with the sizes and checksum in the
+    /// archive's headers updated to match).
+    pub fn gamestate_range(&self) -> Range<usize> {
+        let offset = self.header.header_len();
+        match &self.kind {
+            FileKind::Text(x) | FileKind::Binary(x) => offset..offset + x.len(),
+            FileKind::Zip(Ck3Zip { gamestate, .. }) => {
+                offset + gamestate.data_start..offset + gamestate.data_end
+            }
+        }
+    }
+
     pub fn meta(&self) -> Ck3Meta<'a> {
         match &self.kind {
             FileKind::Text(x) => {
@@ -149,18 +203,25 @@ impl<'a> Ck3File<'a> {
     /// If the file is a zip, the zip contents will be inflated into the zip
     /// sink before being parsed
     pub fn parse(&self, zip_sink: &'a mut Vec<u8>) -> Result<Ck3ParsedFile<'a>, Ck3Error> {
-        match &self.kind {
+        self.parse_with_budget(zip_sink, TokenBudget::new())
+    }
+
+    /// Parses the entire file while drawing from the given budget. The
+    /// budget is carried over to the deserializers of the parsed file, so
+    /// that parsing and deserialization share the same limits.
+    pub fn parse_with_budget(
+        &self,
+        zip_sink: &'a mut Vec<u8>,
+        budget: TokenBudget,
+    ) -> Result<Ck3ParsedFile<'a>, Ck3Error> {
+        let kind = match &self.kind {
             FileKind::Text(x) => {
-                let text = Ck3Text::from_raw(x)?;
-                Ok(Ck3ParsedFile {
-                    kind: Ck3ParsedFileKind::Text(text),
-                })
+                budget.spend_document(x, false)?;
+                Ck3ParsedFileKind::Text(Ck3Text::from_raw(x)?)
             }
             FileKind::Binary(x) => {
-                let binary = Ck3Binary::from_raw(x, self.header.clone())?;
-                Ok(Ck3ParsedFile {
-                    kind: Ck3ParsedFileKind::Binary(binary),
-                })
+                budget.spend_document(x, true)?;
+                Ck3ParsedFileKind::Binary(Ck3Binary::from_raw(x, self.header.clone())?)
             }
             FileKind::Zip(Ck3Zip {
                 archive,
@@ -170,20 +231,17 @@ impl<'a> Ck3File<'a> {
             }) => {
                 let zip = archive.retrieve_file(*gamestate);
                 zip.read_to_end(zip_sink)?;
+                budget.spend_document(zip_sink, !*is_text)?;
 
                 if *is_text {
-                    let text = Ck3Text::from_raw(zip_sink)?;
-                    Ok(Ck3ParsedFile {
-                        kind: Ck3ParsedFileKind::Text(text),
-                    })
+                    Ck3ParsedFileKind::Text(Ck3Text::from_raw(zip_sink)?)
                 } else {
-                    let binary = Ck3Binary::from_raw(zip_sink, self.header.clone())?;
-                    Ok(Ck3ParsedFile {
-                        kind: Ck3ParsedFileKind::Binary(binary),
-                    })
+                    Ck3ParsedFileKind::Binary(Ck3Binary::from_raw(zip_sink, self.header.clone())?)
                 }
             }
-        }
+        };
+
+        Ok(Ck3ParsedFile { kind, budget })
     }
 
     pub fn melter(&self) -> Ck3Melter<'a> {
@@ -200,6 +258,153 @@ impl<'a> Ck3File<'a> {
             }
         }
     }
+
+    /// Melts the file lazily, yielding one line at a time
+    ///
+    /// See [`Ck3Melter::melt_lines`]
+    pub fn melt_lines<'res, R>(
+        &self,
+        resolver: &'res R,
+        options: MeltOptions,
+    ) -> MeltLines<'a, 'res, R>
+    where
+        R: TokenResolver,
+    {
+        self.melter().options(options).melt_lines(resolver)
+    }
+
+    /// Melts the file into a newly allocated buffer
+    pub fn melt_to_vec<R>(
+        &self,
+        resolver: &R,
+        options: MeltOptions,
+    ) -> Result<(Vec<u8>, MeltedDocument), Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let mut out = Vec::new();
+        let doc = self.melter().options(options).melt(&mut out, resolver)?;
+        Ok((out, doc))
+    }
+
+    /// Computes the size of the melted file without retaining the melted
+    /// output
+    pub fn melted_size<R>(&self, resolver: &R, options: MeltOptions) -> Result<u64, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let mut counter = ByteCounter(0);
+        self.melter()
+            .options(options)
+            .melt(&mut counter, resolver)?;
+        Ok(counter.0)
+    }
+
+    /// Melts the file into a string, erroring if the melted output is not
+    /// valid UTF-8
+    pub fn melt_to_string<R>(
+        &self,
+        resolver: &R,
+        options: MeltOptions,
+    ) -> Result<(String, MeltedDocument), Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let (out, doc) = self.melt_to_vec(resolver, options)?;
+        Ok((crate::melt::utf8_to_string(out)?, doc))
+    }
+
+    /// Reports whether character gold is stored as a bare float or as an
+    /// object so that consumers can select the appropriate model
+    ///
+    /// Only the gamestate up to the first character's gold is scanned.
+    pub fn gold_representation<R>(&self, resolver: &R) -> Result<GoldRepr, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let (reader, binary) = self.gamestate_reader();
+        crate::inspect::gold_representation(reader, binary, resolver)
+    }
+
+    /// Scans a binary save for integers that are decoded as dates but fall
+    /// outside of a plausible range of years (ie: year 0 or beyond 10000),
+    /// which usually indicates that the date heuristic misidentified a
+    /// number. Plaintext saves write dates verbatim so are never reported.
+    pub fn validate_dates<R>(&self, resolver: &R) -> Result<Vec<DateAnomaly>, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        match self.gamestate_reader() {
+            (reader, true) => crate::inspect::validate_dates(reader, resolver),
+            (_, false) => Ok(Vec::new()),
+        }
+    }
+
+    /// Computes aggregate figures of the gamestate (character counts, titles,
+    /// wars, and gold) in a single streaming pass, which is cheaper than
+    /// deserializing the gamestate to query each of them
+    pub fn statistics<R>(&self, resolver: &R) -> Result<SaveStatistics, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let (reader, binary) = self.gamestate_reader();
+        crate::inspect::statistics(reader, binary, resolver)
+    }
+
+    /// Returns a streaming reader of the (inflated) gamestate and whether it
+    /// is binary encoded
+    fn gamestate_reader(&self) -> (Box<dyn Read + 'a>, bool) {
+        match &self.kind {
+            FileKind::Text(x) => (Box::new(*x), false),
+            FileKind::Binary(x) => (Box::new(*x), true),
+            FileKind::Zip(x) => (
+                Box::new(x.archive.retrieve_file(x.gamestate).reader()),
+                !x.is_text,
+            ),
+        }
+    }
+
+    /// Computes a canonical fingerprint of the save for deduplication
+    ///
+    /// Saves that are byte-different but semantically identical will share a
+    /// fingerprint. The save is melted and then hashed with the ordering of
+    /// keys within an object, the RNG state, and float jitter (beyond 3
+    /// decimal places) normalized away.
+    pub fn fingerprint<R>(&self, resolver: &R) -> Result<[u8; 32], Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        self.melted_digest(resolver, crate::hash::canonical_digest)
+    }
+
+    /// Computes a canonical hash of only the gamestate
+    ///
+    /// The same as [`Ck3File::fingerprint`] except that the save id line and
+    /// the metadata are excluded, so that identical gamestates saved with
+    /// different headers (eg: an autosave and a manual save) hash the same.
+    pub fn gamestate_hash<R>(&self, resolver: &R) -> Result<[u8; 32], Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        self.melted_digest(resolver, crate::hash::gamestate_digest)
+    }
+
+    /// Melts the save and computes the digest of the melted body
+    fn melted_digest<R>(
+        &self,
+        resolver: &R,
+        digest: fn(&TextTape) -> [u8; 32],
+    ) -> Result<[u8; 32], Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let mut out = Vec::new();
+        self.melter().melt(&mut out, resolver)?;
+        let header = SaveHeader::from_slice(&out)?;
+        let tape =
+            TextTape::from_slice(&out[header.header_len()..]).map_err(Ck3ErrorKind::Parse)?;
+        Ok(digest(&tape))
+    }
 }
 
 /// Holds the metadata section of the save
@@ -225,40 +430,59 @@ impl<'a> Ck3MetaKind<'a> {
             Ck3MetaKind::ZipBinary(x) | Ck3MetaKind::ZipText(x) => x.size,
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 impl<'a> Ck3Meta<'a> {
+    /// Metadata that directly follows the save header
+    pub(crate) fn inlined(data: &'a [u8], header: SaveHeader) -> Self {
+        let kind = if header.kind().is_binary() {
+            Ck3MetaKind::InlinedBinary(data)
+        } else {
+            Ck3MetaKind::InlinedText(data)
+        };
+
+        Ck3Meta { kind, header }
+    }
+
     pub fn header(&self) -> &SaveHeader {
         &self.header
     }
 
-    pub fn kind(&self) -> &Ck3MetaKind {
+    pub fn kind(&self) -> &Ck3MetaKind<'_> {
         &self.kind
     }
 
-    pub fn parse(&self, mut zip_sink: &'a mut Vec<u8>) -> Result<Ck3ParsedFile<'a>, Ck3Error> {
+    pub fn parse(&self, zip_sink: &'a mut Vec<u8>) -> Result<Ck3ParsedFile<'a>, Ck3Error> {
         match &self.kind {
             Ck3MetaKind::InlinedText(x) => Ck3Text::from_raw(x).map(|kind| Ck3ParsedFile {
                 kind: Ck3ParsedFileKind::Text(kind),
+                budget: TokenBudget::new(),
             }),
             Ck3MetaKind::InlinedBinary(x) => {
                 Ck3Binary::from_raw(x, self.header.clone()).map(|kind| Ck3ParsedFile {
                     kind: Ck3ParsedFileKind::Binary(kind),
+                    budget: TokenBudget::new(),
                 })
             }
             Ck3MetaKind::ZipText(file) => {
                 let start_len = zip_sink.len();
-                file.read_to_end(&mut zip_sink)?;
+                file.read_to_end(zip_sink)?;
                 Ck3Text::from_raw(&zip_sink[start_len..]).map(|kind| Ck3ParsedFile {
                     kind: Ck3ParsedFileKind::Text(kind),
+                    budget: TokenBudget::new(),
                 })
             }
             Ck3MetaKind::ZipBinary(file) => {
                 let start_len = zip_sink.len();
-                file.read_to_end(&mut zip_sink)?;
+                file.read_to_end(zip_sink)?;
                 Ck3Binary::from_raw(&zip_sink[start_len..], self.header.clone()).map(|kind| {
                     Ck3ParsedFile {
                         kind: Ck3ParsedFileKind::Binary(kind),
+                        budget: TokenBudget::new(),
                     }
                 })
             }
@@ -292,11 +516,12 @@ pub enum Ck3ParsedFileKind<'a> {
 /// An Ck3 file that has been parsed
 pub struct Ck3ParsedFile<'a> {
     kind: Ck3ParsedFileKind<'a>,
+    budget: TokenBudget,
 }
 
 impl<'a> Ck3ParsedFile<'a> {
     /// Returns the file as text
-    pub fn as_text(&self) -> Option<&Ck3Text> {
+    pub fn as_text(&self) -> Option<&Ck3Text<'_>> {
         match &self.kind {
             Ck3ParsedFileKind::Text(x) => Some(x),
             _ => None,
@@ -304,7 +529,7 @@ impl<'a> Ck3ParsedFile<'a> {
     }
 
     /// Returns the file as binary
-    pub fn as_binary(&self) -> Option<&Ck3Binary> {
+    pub fn as_binary(&self) -> Option<&Ck3Binary<'_>> {
         match &self.kind {
             Ck3ParsedFileKind::Binary(x) => Some(x),
             _ => None,
@@ -312,24 +537,60 @@ impl<'a> Ck3ParsedFile<'a> {
     }
 
     /// Returns the kind of file (binary or text)
-    pub fn kind(&self) -> &Ck3ParsedFileKind {
+    pub fn kind(&self) -> &Ck3ParsedFileKind<'_> {
         &self.kind
     }
 
     /// Prepares the file for deserialization into a custom structure
-    pub fn deserializer<'b, RES>(&'b self, resolver: &'b RES) -> Ck3Deserializer<RES>
+    pub fn deserializer<'b, RES>(&'b self, resolver: &'b RES) -> Ck3Deserializer<'b, 'b, RES>
     where
         RES: TokenResolver,
     {
         match &self.kind {
             Ck3ParsedFileKind::Text(x) => Ck3Deserializer {
                 kind: Ck3DeserializerKind::Text(TextDeserializer::from_utf8_tape(&x.tape)),
+                budget: self.budget.clone(),
             },
             Ck3ParsedFileKind::Binary(x) => Ck3Deserializer {
                 kind: Ck3DeserializerKind::Binary(x.deserializer(resolver)),
+                budget: self.budget.clone(),
             },
         }
     }
+
+    /// Prepares the file for deserialization with the given binary flavor
+    /// instead of the one detected from the save's version. The flavor is
+    /// unused for plaintext files.
+    pub fn deserializer_with_flavor<'b, RES>(
+        &'b self,
+        resolver: &'b RES,
+        flavor: Box<dyn Ck3BinaryFlavor>,
+    ) -> Ck3Deserializer<'b, 'b, RES>
+    where
+        RES: TokenResolver,
+    {
+        match &self.kind {
+            Ck3ParsedFileKind::Text(_) => self.deserializer(resolver),
+            Ck3ParsedFileKind::Binary(x) => Ck3Deserializer {
+                kind: Ck3DeserializerKind::Binary(x.deserializer_with_flavor(resolver, flavor)),
+                budget: self.budget.clone(),
+            },
+        }
+    }
+}
+
+/// A writer that discards its input and only counts the bytes written
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -434,13 +695,14 @@ impl<'a> Ck3Text<'a> {
         Ok(Ck3Text { tape })
     }
 
-    pub fn reader(&self) -> ObjectReader<Utf8Encoding> {
+    pub fn reader(&self) -> ObjectReader<'_, '_, Utf8Encoding> {
         self.tape.utf8_reader()
     }
 
     pub fn deserializer<'b, T>(&'b self) -> Ck3Deserializer<'a, 'b, ()> {
         Ck3Deserializer {
             kind: Ck3DeserializerKind::Text(TextDeserializer::from_utf8_tape(&self.tape)),
+            budget: TokenBudget::new(),
         }
     }
 }
@@ -463,13 +725,28 @@ impl<'a> Ck3Binary<'a> {
         Ok(Ck3Binary { tape, header })
     }
 
-    pub fn deserializer<'b, RES>(&'b self, resolver: &'b RES) -> Ck3BinaryDeserializer<RES>
+    pub fn deserializer<'b, RES>(&'b self, resolver: &'b RES) -> Ck3BinaryDeserializer<'b, 'b, RES>
+    where
+        RES: TokenResolver,
+    {
+        self.deserializer_with_flavor(resolver, flavor_from_tape(&self.tape))
+    }
+
+    /// Prepares the data for deserialization with the given binary flavor
+    /// instead of the one detected from the save's version
+    pub fn deserializer_with_flavor<'b, RES>(
+        &'b self,
+        resolver: &'b RES,
+        flavor: Box<dyn Ck3BinaryFlavor>,
+    ) -> Ck3BinaryDeserializer<'b, 'b, RES>
     where
         RES: TokenResolver,
     {
         Ck3BinaryDeserializer {
-            deser: BinaryDeserializer::builder_flavor(flavor_from_tape(&self.tape))
-                .from_tape(&self.tape, resolver),
+            deser: BinaryDeserializer::builder_flavor(flavor).from_tape(&self.tape, resolver),
+            tape: &self.tape,
+            resolver,
+            budget: TokenBudget::new(),
         }
     }
 }
@@ -482,6 +759,7 @@ enum Ck3DeserializerKind<'data, 'tape, RES> {
 /// A deserializer for custom structures
 pub struct Ck3Deserializer<'data, 'tape, RES> {
     kind: Ck3DeserializerKind<'data, 'tape, RES>,
+    budget: TokenBudget,
 }
 
 impl<'data, 'tape, RES> Ck3Deserializer<'data, 'tape, RES>
@@ -495,24 +773,72 @@ where
         self
     }
 
+    /// Abort with [`Ck3ErrorKind::BudgetExceeded`] once more than the given
+    /// number of values have been deserialized. Any tokens spent while
+    /// parsing with [`Ck3File::parse_with_budget`] count against the limit.
+    pub fn token_budget(&mut self, max_tokens: usize) -> &mut Self {
+        self.budget = self.budget.clone().max_tokens(max_tokens as u64);
+        self
+    }
+
+    /// Abort with [`Ck3ErrorKind::BudgetExceeded`] once the deadline has
+    /// passed. The deadline is checked periodically during deserialization.
+    pub fn deadline(&mut self, deadline: Instant) -> &mut Self {
+        self.budget = self.budget.clone().deadline(deadline);
+        self
+    }
+
     pub fn deserialize<T>(&self) -> Result<T, Ck3Error>
     where
         T: Deserialize<'data>,
+    {
+        self.deserialize_seed(std::marker::PhantomData)
+    }
+
+    /// Drive deserialization with a stateful seed, so that callers can, for
+    /// instance, extract values into a pre-allocated buffer
+    pub fn deserialize_seed<S>(&self, seed: S) -> Result<S::Value, Ck3Error>
+    where
+        S: DeserializeSeed<'data>,
     {
         match &self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize()
-                .map_err(|e| Ck3Error::new(Ck3ErrorKind::Deserialize(e))),
-            Ck3DeserializerKind::Binary(x) => x.deserialize(),
+            Ck3DeserializerKind::Text(x) => seed
+                .deserialize(Budgeted::new(x, &self.budget))
+                .map_err(|e| {
+                    self.budget
+                        .translate(e, |e| Ck3Error::new(Ck3ErrorKind::Deserialize(e)))
+                }),
+            Ck3DeserializerKind::Binary(x) => x.deserialize_budgeted(seed, &self.budget),
         }
     }
+
+    /// Deserializes the document while also reporting the binary token ids
+    /// that the resolver could not resolve, which can help grow a token file.
+    ///
+    /// The report covers every token in the document, including those within
+    /// sections that `T` does not deserialize. Plaintext documents have no
+    /// tokens so the report is always empty.
+    pub fn deserialize_with_report<T>(&self) -> Result<(T, HashSet<u16>), Ck3Error>
+    where
+        T: Deserialize<'data>,
+    {
+        let result = self.deserialize()?;
+        let unknown_tokens = match &self.kind {
+            Ck3DeserializerKind::Text(_) => HashSet::new(),
+            Ck3DeserializerKind::Binary(x) => x.unresolved_tokens(),
+        };
+        Ok((result, unknown_tokens))
+    }
 }
 
 fn translate_deserialize_error(e: jomini::Error) -> Ck3Error {
     let kind = match e.kind() {
         jomini::ErrorKind::Deserialize(x) => match x.kind() {
             &jomini::DeserializeErrorKind::UnknownToken { token_id } => {
-                Ck3ErrorKind::UnknownToken { token_id }
+                Ck3ErrorKind::UnknownToken {
+                    token_id,
+                    offset: None,
+                }
             }
             _ => Ck3ErrorKind::Deserialize(e),
         },
@@ -530,15 +856,12 @@ macro_rules! forward_deserialization {
         where
             V: serde::de::Visitor<'de>,
         {
-            match self.kind {
-                Ck3DeserializerKind::Text(x) => {
-                    x.$method(visitor).map_err(translate_deserialize_error)
-                }
-                Ck3DeserializerKind::Binary(x) => x
-                    .deser
-                    .$method(visitor)
-                    .map_err(translate_deserialize_error),
-            }
+            let budget = self.budget;
+            let result = match self.kind {
+                Ck3DeserializerKind::Text(x) => Budgeted::new(&x, &budget).$method(visitor),
+                Ck3DeserializerKind::Binary(x) => Budgeted::new(&x.deser, &budget).$method(visitor),
+            };
+            result.map_err(|e| budget.translate(e, translate_deserialize_error))
         }
     };
 }
@@ -581,15 +904,16 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_unit_struct(name, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_unit_struct(name, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_unit_struct(name, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_unit_struct(name, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -600,30 +924,32 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_newtype_struct(name, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_newtype_struct(name, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_newtype_struct(name, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_newtype_struct(name, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_tuple(len, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_tuple(len, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_tuple(len, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_tuple(len, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 
     fn deserialize_tuple_struct<V>(
@@ -635,15 +961,16 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_tuple_struct(name, len, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_tuple_struct(name, len, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_tuple_struct(name, len, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_tuple_struct(name, len, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 
     fn deserialize_struct<V>(
@@ -655,15 +982,16 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_struct(name, fields, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_struct(name, fields, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_struct(name, fields, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_struct(name, fields, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 
     fn deserialize_enum<V>(
@@ -675,21 +1003,25 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.kind {
-            Ck3DeserializerKind::Text(x) => x
-                .deserialize_enum(name, variants, visitor)
-                .map_err(translate_deserialize_error),
-            Ck3DeserializerKind::Binary(x) => x
-                .deser
-                .deserialize_enum(name, variants, visitor)
-                .map_err(translate_deserialize_error),
-        }
+        let budget = self.budget;
+        let result = match self.kind {
+            Ck3DeserializerKind::Text(x) => {
+                Budgeted::new(&x, &budget).deserialize_enum(name, variants, visitor)
+            }
+            Ck3DeserializerKind::Binary(x) => {
+                Budgeted::new(&x.deser, &budget).deserialize_enum(name, variants, visitor)
+            }
+        };
+        result.map_err(|e| budget.translate(e, translate_deserialize_error))
     }
 }
 
 /// Deserializes binary data into custom structures
 pub struct Ck3BinaryDeserializer<'data, 'tape, RES> {
     deser: BinaryDeserializer<'tape, 'data, 'tape, RES, Box<dyn Ck3BinaryFlavor>>,
+    tape: &'tape BinaryTape<'data>,
+    resolver: &'tape RES,
+    budget: TokenBudget,
 }
 
 impl<'data, 'tape, RES> Ck3BinaryDeserializer<'data, 'tape, RES>
@@ -701,19 +1033,435 @@ where
         self
     }
 
+    /// See [`Ck3Deserializer::token_budget`]
+    pub fn token_budget(&mut self, max_tokens: usize) -> &mut Self {
+        self.budget = self.budget.clone().max_tokens(max_tokens as u64);
+        self
+    }
+
+    /// See [`Ck3Deserializer::deadline`]
+    pub fn deadline(&mut self, deadline: Instant) -> &mut Self {
+        self.budget = self.budget.clone().deadline(deadline);
+        self
+    }
+
     pub fn deserialize<T>(&self) -> Result<T, Ck3Error>
     where
         T: Deserialize<'data>,
     {
-        let result = self.deser.deserialize().map_err(|e| match e.kind() {
-            jomini::ErrorKind::Deserialize(e2) => match e2.kind() {
-                &jomini::DeserializeErrorKind::UnknownToken { token_id } => {
-                    Ck3ErrorKind::UnknownToken { token_id }
+        self.deserialize_seed(std::marker::PhantomData)
+    }
+
+    /// See [`Ck3Deserializer::deserialize_seed`]
+    pub fn deserialize_seed<S>(&self, seed: S) -> Result<S::Value, Ck3Error>
+    where
+        S: DeserializeSeed<'data>,
+    {
+        self.deserialize_budgeted(seed, &self.budget)
+    }
+
+    fn deserialize_budgeted<S>(&self, seed: S, budget: &TokenBudget) -> Result<S::Value, Ck3Error>
+    where
+        S: DeserializeSeed<'data>,
+    {
+        seed.deserialize(Budgeted::new(&self.deser, budget))
+            .map_err(|e| budget.translate(e, binary_deserialize_error))
+    }
+
+    /// See [`Ck3Deserializer::deserialize_with_report`]
+    pub fn deserialize_with_report<T>(&self) -> Result<(T, HashSet<u16>), Ck3Error>
+    where
+        T: Deserialize<'data>,
+    {
+        let result = self.deserialize()?;
+        Ok((result, self.unresolved_tokens()))
+    }
+
+    fn unresolved_tokens(&self) -> HashSet<u16> {
+        self.tape
+            .tokens()
+            .iter()
+            .filter_map(|token| match token {
+                BinaryToken::Token(id) if self.resolver.resolve(*id).is_none() => Some(*id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn binary_deserialize_error(e: jomini::Error) -> Ck3Error {
+    let kind = match e.kind() {
+        jomini::ErrorKind::Deserialize(e2) => match e2.kind() {
+            &jomini::DeserializeErrorKind::UnknownToken { token_id } => {
+                Ck3ErrorKind::UnknownToken {
+                    token_id,
+                    offset: None,
                 }
-                _ => Ck3ErrorKind::Deserialize(e),
-            },
+            }
             _ => Ck3ErrorKind::Deserialize(e),
-        })?;
-        Ok(result)
+        },
+        _ => Ck3ErrorKind::Deserialize(e),
+    };
+
+    Ck3Error::new(kind)
+}
+
+/// Deserializes the metadata of a save from bytes that have already been
+/// split off from the rest of the file
+///
+/// The bytes must start with the save id line, as its kind determines
+/// whether the metadata is binary or plaintext.
+pub fn deserialize_header<R>(data: &[u8], resolver: &R) -> Result<HeaderOwned, Ck3Error>
+where
+    R: TokenResolver,
+{
+    let header = SaveHeader::from_slice(data)?;
+    let data = &data[header.header_len()..];
+    if header.kind().is_binary() {
+        let tape = BinaryTape::from_slice(data).map_err(Ck3ErrorKind::Parse)?;
+        BinaryDeserializer::builder_flavor(flavor_from_tape(&tape))
+            .from_tape(&tape, resolver)
+            .deserialize()
+            .map_err(binary_deserialize_error)
+    } else if header.kind().is_text() {
+        let tape = TextTape::from_slice(data).map_err(Ck3ErrorKind::Parse)?;
+        TextDeserializer::from_utf8_tape(&tape)
+            .deserialize()
+            .map_err(|e| Ck3ErrorKind::Deserialize(e).into())
+    } else {
+        Err(Ck3ErrorKind::InvalidHeader.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{binary_save, id, living, resolver, zip};
+    use jomini::binary::Token;
+
+    #[test]
+    fn test_encoding() {
+        let binary = binary_save(&living());
+        let file = Ck3File::from_slice(&binary).unwrap();
+        assert_eq!(file.encoding(), Encoding::Binary);
+
+        let mut save = b"SAV01030000000000000000\n".to_vec();
+        save.extend_from_slice(&zip(&binary[24..]));
+        let file = Ck3File::from_slice(&save).unwrap();
+        assert_eq!(file.encoding(), Encoding::BinaryZip);
+
+        let text = b"meta_data={ version=\"1.9.0\" }\nliving={ }\n";
+        let mut save = b"SAV01000000000000000000\n".to_vec();
+        save.extend_from_slice(text);
+        let file = Ck3File::from_slice(&save).unwrap();
+        assert_eq!(file.encoding(), Encoding::Text);
+
+        let mut save = b"SAV01020000000000000000\n".to_vec();
+        save.extend_from_slice(&zip(text));
+        let file = Ck3File::from_slice(&save).unwrap();
+        assert_eq!(file.encoding(), Encoding::TextZip);
+    }
+
+    #[test]
+    fn test_gamestate_range() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let range = file.gamestate_range();
+        assert_eq!(range, 24..data.len());
+    }
+
+    #[test]
+    fn test_gamestate_range_zip() {
+        let data = binary_save(&living());
+        let mut save = b"SAV01030000000000000000\n".to_vec();
+        save.extend_from_slice(&zip(&data[24..]));
+        let file = Ck3File::from_slice(&save).unwrap();
+        assert_eq!(file.encoding(), Encoding::BinaryZip);
+
+        // The range points at the compressed entry, not the inflated size
+        let range = file.gamestate_range();
+        assert!(range.start > 24 && range.end < save.len());
+        assert_ne!(range.len(), file.size());
+
+        let mut inflated = Vec::new();
+        flate2::read::DeflateDecoder::new(&save[range])
+            .read_to_end(&mut inflated)
+            .unwrap();
+        assert_eq!(inflated, &data[24..]);
+    }
+
+    #[test]
+    fn test_flavor() {
+        let save = |version: i32| {
+            let tokens = [
+                id("meta_data"),
+                Token::Equal,
+                Token::Open,
+                Token::Id(1423),
+                Token::Equal,
+                Token::I32(version),
+                Token::Close,
+            ];
+            let mut out = b"SAV01010000000000000000\n".to_vec();
+            for token in &tokens {
+                token.write(&mut out).unwrap();
+            }
+            out
+        };
+
+        let data = save(3);
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_eq!(file.flavor().unwrap(), Some(Ck3FlavorKind::Flavor10));
+
+        let data = save(19);
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_eq!(file.flavor().unwrap(), Some(Ck3FlavorKind::Flavor15));
+
+        let data = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.0.2\" }\n";
+        let file = Ck3File::from_slice(&data[..]).unwrap();
+        assert_eq!(file.flavor().unwrap(), None);
+    }
+
+    #[test]
+    fn test_melt_lines_text() {
+        let data = b"SAV0100a40f789f00000000\nmeta_data={\n\tversion=\"1.0.2\"\n}\n";
+        let file = Ck3File::from_slice(&data[..]).unwrap();
+        let resolver = resolver();
+        let lines = file
+            .melt_lines(&resolver, MeltOptions::new())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines.join("\n") + "\n", std::str::from_utf8(data).unwrap());
+    }
+
+    #[test]
+    fn test_melt_to_vec_and_string() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let mut expected = Vec::new();
+        file.melter().melt(&mut expected, &resolver()).unwrap();
+
+        let (out, _) = file.melt_to_vec(&resolver(), MeltOptions::new()).unwrap();
+        assert_eq!(out, expected);
+
+        let (out, doc) = file
+            .melt_to_string(&resolver(), MeltOptions::new())
+            .unwrap();
+        assert_eq!(out.as_bytes(), expected.as_slice());
+        assert!(doc.unknown_tokens().is_empty());
+    }
+
+    #[test]
+    fn test_melted_size() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let (out, _) = file.melt_to_vec(&resolver(), MeltOptions::new()).unwrap();
+        let size = file.melted_size(&resolver(), MeltOptions::new()).unwrap();
+        assert_eq!(size, out.len() as u64);
+
+        let data = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.0.2\" }\n";
+        let file = Ck3File::from_slice(&data[..]).unwrap();
+        let size = file.melted_size(&resolver(), MeltOptions::new()).unwrap();
+        assert_eq!(size, data.len() as u64);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_key_order() {
+        let resolver = resolver();
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let expected = file.fingerprint(&resolver).unwrap();
+
+        let text = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.0.2\" save_game_version=3 }\nliving={ 2={ first_name=Halfdan } 1={ first_name=\"Ivar\" } }\n";
+        let file = Ck3File::from_slice(&text[..]).unwrap();
+        assert_eq!(file.fingerprint(&resolver).unwrap(), expected);
+
+        let text = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.0.2\" save_game_version=3 }\nliving={ 2={ first_name=Ivar } 1={ first_name=\"Halfdan\" } }\n";
+        let file = Ck3File::from_slice(&text[..]).unwrap();
+        assert_ne!(file.fingerprint(&resolver).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_gamestate_hash_ignores_header() {
+        let resolver = resolver();
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let expected = file.gamestate_hash(&resolver).unwrap();
+
+        let text = b"SAV0102a40f789f00000000\nmeta_data={ version=\"1.0.3\" save_game_version=3 }\nliving={ 2={ first_name=Halfdan } 1={ first_name=\"Ivar\" } }\n";
+        let changed = Ck3File::from_slice(&text[..]).unwrap();
+        assert_eq!(changed.gamestate_hash(&resolver).unwrap(), expected);
+        assert_ne!(
+            changed.fingerprint(&resolver).unwrap(),
+            file.fingerprint(&resolver).unwrap()
+        );
+
+        let text = b"SAV0102a40f789f00000000\nmeta_data={ version=\"1.0.3\" save_game_version=3 }\nliving={ 2={ first_name=Halfdan } }\n";
+        let file = Ck3File::from_slice(&text[..]).unwrap();
+        assert_ne!(file.gamestate_hash(&resolver).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_deserialize_token_budget() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let resolver = resolver();
+        let err = parsed
+            .deserializer(&resolver)
+            .token_budget(5)
+            .deserialize::<HeaderOwned>()
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        let header: HeaderOwned = parsed
+            .deserializer(&resolver)
+            .token_budget(1000)
+            .deserialize()
+            .unwrap();
+        assert_eq!(header.meta_data.version, "1.0.2");
+
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let err = parsed
+            .deserializer(&resolver)
+            .deadline(Instant::now())
+            .deserialize::<HeaderOwned>()
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+    }
+
+    #[test]
+    fn test_parse_with_budget() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let resolver = resolver();
+
+        let mut zip_sink = Vec::new();
+        let budget = TokenBudget::new().max_tokens(20);
+        let err = file.parse_with_budget(&mut zip_sink, budget).err().unwrap();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        // Parsing fits within the budget but the deserialization draws from
+        // the same counter
+        let mut zip_sink = Vec::new();
+        let budget = TokenBudget::new().max_tokens(30);
+        let parsed = file
+            .parse_with_budget(&mut zip_sink, budget.clone())
+            .unwrap();
+        assert_eq!(budget.spent(), 28);
+        let err = parsed
+            .deserializer(&resolver)
+            .deserialize::<HeaderOwned>()
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        let mut zip_sink = Vec::new();
+        let budget = TokenBudget::new().max_tokens(1000);
+        let parsed = file.parse_with_budget(&mut zip_sink, budget).unwrap();
+        let header: HeaderOwned = parsed.deserializer(&resolver).deserialize().unwrap();
+        assert_eq!(header.meta_data.version, "1.0.2");
+    }
+
+    /// Collects the ids of living characters without deserializing the
+    /// characters themselves
+    struct LivingIds<'a>(&'a mut Vec<u64>);
+
+    impl<'de> DeserializeSeed<'de> for LivingIds<'_> {
+        type Value = ();
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            #[derive(Deserialize)]
+            struct Gamestate {
+                living: std::collections::BTreeMap<u64, serde::de::IgnoredAny>,
+            }
+
+            let game = Gamestate::deserialize(deserializer)?;
+            self.0.extend(game.living.keys());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_deserialize_seed() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let resolver = resolver();
+
+        let mut ids = Vec::with_capacity(2);
+        parsed
+            .deserializer(&resolver)
+            .deserialize_seed(LivingIds(&mut ids))
+            .unwrap();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_deserialize_with_report() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let mut resolver = resolver();
+        resolver.retain(|_, name| *name != "first_name");
+
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let (game, unknown) = parsed
+            .deserializer(&resolver)
+            .deserialize_with_report::<crate::models::Gamestate>()
+            .unwrap();
+        assert_eq!(game.living.len(), 2);
+        let Token::Id(first_name) = id("first_name") else {
+            unreachable!()
+        };
+        assert_eq!(unknown, HashSet::from([first_name]));
+
+        // Tokens are reported even when the deserialized type skips them
+        let (header, unknown) = parsed
+            .deserializer(&resolver)
+            .deserialize_with_report::<HeaderOwned>()
+            .unwrap();
+        assert_eq!(header.meta_data.version, "1.0.2");
+        assert_eq!(unknown, HashSet::from([first_name]));
+
+        let err = parsed
+            .deserializer(&resolver)
+            .token_budget(5)
+            .deserialize_with_report::<HeaderOwned>()
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+    }
+
+    #[test]
+    fn test_deserialize_with_flavor() {
+        #[derive(Deserialize)]
+        struct Gold {
+            gold: f64,
+        }
+
+        let data = binary_save(&[
+            id("gold"),
+            Token::Equal,
+            Token::F64(12_345_i64.to_le_bytes()),
+        ]);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let resolver = resolver();
+
+        let game: Gold = parsed.deserializer(&resolver).deserialize().unwrap();
+        assert_eq!(game.gold, 12.345);
+
+        let flavor = Box::new(crate::melt::tests::CentFlavor(Utf8Encoding::new()));
+        let game: Gold = parsed
+            .deserializer_with_flavor(&resolver, flavor)
+            .deserialize()
+            .unwrap();
+        assert_eq!(game.gold, 123.45);
     }
 }
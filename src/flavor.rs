@@ -14,11 +14,22 @@ pub(crate) fn reencode_float(f: f64) -> f64 {
     num / 100_000.0
 }
 
-pub(crate) trait Ck3BinaryFlavor: BinaryFlavor + jomini::Encoding {
-    /// Designates this flavor as having floats that have more than two binary representations
+/// A binary flavor with the CK3 specific knobs used when melting
+///
+/// Implement this to decode saves from a patch that changed how values are
+/// encoded before this crate supports it, and supply it through
+/// [`MeltOptions::with_flavor`](crate::MeltOptions::with_flavor) or
+/// [`Ck3ParsedFile::deserializer_with_flavor`](crate::file::Ck3ParsedFile::deserializer_with_flavor).
+/// Flavors must be `Send` and `Sync` so that melt options holding one can
+/// be shared across threads.
+pub trait Ck3BinaryFlavor: BinaryFlavor + jomini::Encoding + Send + Sync {
+    /// Designates this flavor as having floats that have more than two binary
+    /// representations, so that fields known to use the alternate (Q49.15)
+    /// encoding, like a character's gold, are reencoded when melted
     fn float_reencoding(&self) -> bool;
 
     /// Even if the following quoted strings are found, write them out unquoted
+    /// when melted (eg: the entries of `traits_lookup`)
     fn unquote_token(&self, token: &str) -> bool;
 }
 
@@ -32,12 +43,79 @@ impl<T: Ck3BinaryFlavor + ?Sized> Ck3BinaryFlavor for Box<T> {
     }
 }
 
+/// The token id of `save_game_version`, which is the first field of an
+/// ironman save's metadata
+const SAVE_GAME_VERSION: u16 = 1423;
+
 pub(crate) fn flavor_from_tape(tape: &BinaryTape) -> Box<dyn Ck3BinaryFlavor> {
-    match tape.tokens() {
-        [_, _, BinaryToken::Token(1423), BinaryToken::I32(x), ..] if *x > 5 => {
-            Box::new(Ck3Flavor15::new())
+    let kind = match tape.tokens() {
+        [_, _, BinaryToken::Token(id), BinaryToken::I32(x), ..] => {
+            Ck3FlavorKind::from_first_field(*id, *x)
+        }
+        _ => Ck3FlavorKind::Flavor10,
+    };
+    kind.flavor()
+}
+
+/// Identifies the binary flavor of a save
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ck3FlavorKind {
+    /// The flavor of saves before 1.5 ([`Ck3Flavor10`])
+    Flavor10,
+
+    /// The flavor of saves from 1.5 onwards ([`Ck3Flavor15`])
+    Flavor15,
+}
+
+impl Ck3FlavorKind {
+    /// The flavor of saves written with the given `save_game_version`
+    pub(crate) fn from_save_game_version(version: i32) -> Self {
+        if version > 5 {
+            Ck3FlavorKind::Flavor15
+        } else {
+            Ck3FlavorKind::Flavor10
+        }
+    }
+
+    /// The flavor of saves whose metadata starts with the given token id
+    /// and integer value
+    fn from_first_field(id: u16, value: i32) -> Self {
+        if id == SAVE_GAME_VERSION {
+            Self::from_save_game_version(value)
+        } else {
+            Ck3FlavorKind::Flavor10
         }
-        _ => Box::new(Ck3Flavor10::new()),
+    }
+
+    pub(crate) fn flavor(self) -> Box<dyn Ck3BinaryFlavor> {
+        match self {
+            Ck3FlavorKind::Flavor10 => Box::new(Ck3Flavor10::new()),
+            Ck3FlavorKind::Flavor15 => Box::new(Ck3Flavor15::new()),
+        }
+    }
+}
+
+/// Detects the flavor from the `save_game_version` at the start of the
+/// binary data
+pub(crate) fn flavor_kind_from_reader<R>(
+    reader: R,
+) -> Result<Ck3FlavorKind, jomini::binary::ReaderError>
+where
+    R: std::io::Read,
+{
+    let mut reader = jomini::binary::TokenReader::new(reader);
+    for _ in 0..3 {
+        reader.read()?;
+    }
+
+    let id = match reader.read()? {
+        jomini::binary::Token::Id(id) => Some(id),
+        _ => None,
+    };
+    reader.read()?;
+    match (id, reader.read()?) {
+        (Some(id), jomini::binary::Token::I32(x)) => Ok(Ck3FlavorKind::from_first_field(id, x)),
+        _ => Ok(Ck3FlavorKind::Flavor10),
     }
 }
 
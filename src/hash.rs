@@ -0,0 +1,159 @@
+/*!
+Content hashing of melted saves.
+
+The digest is computed over the structure of the save rather than its bytes
+so that semantically identical saves (ones that only differ in the order
+of keys within an object, or their RNG state) hash the same. The rules:
+
+ - Fields within an object are order independent
+ - Array elements remain order dependent
+ - Quoted and unquoted scalars are equivalent
+ - Floats are rounded to 3 decimal places
+ - RNG state (`seed` and `random_count`) is ignored
+
+A gamestate digest additionally ignores the top level `meta_data` block.
+*/
+
+use jomini::{TextTape, TextToken};
+use sha2::{Digest, Sha256};
+
+/// Computes the canonical digest of a plaintext save body (ie: without the
+/// save id line)
+pub(crate) fn canonical_digest(tape: &TextTape) -> [u8; 32] {
+    let tokens = tape.tokens();
+    object_digest(tokens, 0, tokens.len())
+}
+
+/// Computes the canonical digest of a plaintext save body without its
+/// metadata
+pub(crate) fn gamestate_digest(tape: &TextTape) -> [u8; 32] {
+    let tokens = tape.tokens();
+    filtered_object_digest(tokens, 0, tokens.len(), |key| {
+        is_rng(key) || key == b"meta_data"
+    })
+}
+
+fn is_rng(key: &[u8]) -> bool {
+    matches!(key, b"seed" | b"random_count")
+}
+
+fn value_end(tokens: &[TextToken], idx: usize) -> usize {
+    match tokens[idx] {
+        TextToken::Array { end, .. } | TextToken::Object { end, .. } => end + 1,
+        TextToken::Header(_) => value_end(tokens, idx + 1),
+        _ => idx + 1,
+    }
+}
+
+fn object_digest(tokens: &[TextToken], start: usize, end: usize) -> [u8; 32] {
+    filtered_object_digest(tokens, start, end, is_rng)
+}
+
+fn filtered_object_digest(
+    tokens: &[TextToken],
+    start: usize,
+    end: usize,
+    skip: impl Fn(&[u8]) -> bool,
+) -> [u8; 32] {
+    let mut entries = Vec::new();
+    let mut idx = start;
+    while idx < end {
+        let key = match &tokens[idx] {
+            TextToken::Unquoted(x) | TextToken::Quoted(x) => x.as_bytes(),
+
+            // Not a well-formed key value pair, so fallback to an order
+            // dependent digest
+            _ => return sequence_digest(tokens, start, end),
+        };
+        idx += 1;
+
+        let mut entry = Sha256::new();
+        entry.update(key);
+        entry.update([0]);
+        if let Some(TextToken::Operator(op)) = tokens.get(idx) {
+            entry.update(op.symbol().as_bytes());
+            idx += 1;
+        }
+
+        if idx >= end {
+            return sequence_digest(tokens, start, end);
+        }
+
+        let next = value_end(tokens, idx);
+        if !skip(key) {
+            entry.update(value_digest(tokens, idx));
+            entries.push(entry.finalize());
+        }
+        idx = next;
+    }
+
+    entries.sort_unstable();
+    let mut hasher = Sha256::new();
+    hasher.update(b"{");
+    for entry in &entries {
+        hasher.update(entry);
+    }
+    hasher.finalize().into()
+}
+
+fn sequence_digest(tokens: &[TextToken], start: usize, end: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"[");
+    let mut idx = start;
+    while idx < end {
+        match tokens[idx] {
+            TextToken::MixedContainer => {}
+            TextToken::Operator(op) => hasher.update(op.symbol().as_bytes()),
+            _ => hasher.update(value_digest(tokens, idx)),
+        }
+        idx = value_end(tokens, idx);
+    }
+    hasher.finalize().into()
+}
+
+fn value_digest(tokens: &[TextToken], idx: usize) -> [u8; 32] {
+    match &tokens[idx] {
+        TextToken::Object { end, mixed: false } => object_digest(tokens, idx + 1, *end),
+        TextToken::Object { end, .. } | TextToken::Array { end, .. } => {
+            sequence_digest(tokens, idx + 1, *end)
+        }
+        TextToken::Header(x) => {
+            let mut hasher = Sha256::new();
+            hasher.update(x.as_bytes());
+            hasher.update(value_digest(tokens, idx + 1));
+            hasher.finalize().into()
+        }
+        token => {
+            let mut hasher = Sha256::new();
+            let scalar = token.as_scalar().map(|x| x.as_bytes()).unwrap_or_default();
+            match normalize_float(scalar) {
+                Some(x) => hasher.update(x.as_bytes()),
+                None => hasher.update(scalar),
+            }
+            hasher.finalize().into()
+        }
+    }
+}
+
+fn normalize_float(data: &[u8]) -> Option<String> {
+    if !data.contains(&b'.') {
+        return None;
+    }
+
+    let x: f64 = std::str::from_utf8(data).ok()?.parse().ok()?;
+    Some(format!("{:.3}", x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_digest_order_independent() {
+        let a = TextTape::from_slice(b"a=1 b={ c=2.00001 d=\"e\" } seed=10 f={ 1 2 }").unwrap();
+        let b = TextTape::from_slice(b"b={ d=e c=2.0 } a=1 seed=20 f={ 1 2 }").unwrap();
+        let c = TextTape::from_slice(b"b={ d=e c=2.0 } a=1 seed=20 f={ 2 1 }").unwrap();
+        assert_eq!(canonical_digest(&a), canonical_digest(&b));
+        assert_ne!(canonical_digest(&a), canonical_digest(&c));
+    }
+}
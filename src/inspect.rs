@@ -0,0 +1,464 @@
+use crate::flavor::{reencode_float, Ck3BinaryFlavor, Ck3Flavor10, Ck3FlavorKind};
+use crate::{Ck3Date, Ck3Error, Ck3ErrorKind};
+use jomini::binary::{self, BinaryFlavor, TokenResolver};
+use jomini::common::PdsDate;
+use jomini::text;
+use std::io::Read;
+
+/// Describes how character gold is encoded within a save
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldRepr {
+    /// Gold is a bare number: `gold=133.04397` (prior to 1.16)
+    Float,
+
+    /// Gold is an object: `gold={ value=133.04397 }` (1.16+)
+    Object,
+
+    /// No character gold was encountered
+    Unknown,
+}
+
+/// Tracks whether the scanner is positioned at the value of a `gold` key
+/// within an `alive_data` block
+#[derive(Debug, Default)]
+struct GoldScanner {
+    stack: Vec<bool>,
+    alive_queued: bool,
+    gold_queued: bool,
+}
+
+impl GoldScanner {
+    fn open(&mut self) -> Option<GoldRepr> {
+        if self.gold_queued {
+            return Some(GoldRepr::Object);
+        }
+
+        self.stack.push(self.alive_queued);
+        self.alive_queued = false;
+        None
+    }
+
+    fn close(&mut self) {
+        let _ = self.stack.pop();
+    }
+
+    fn scalar(&mut self, name: Option<&str>) -> Option<GoldRepr> {
+        if self.gold_queued {
+            return Some(GoldRepr::Float);
+        }
+
+        self.alive_queued = name == Some("alive_data");
+        self.gold_queued = name == Some("gold") && self.stack.last() == Some(&true);
+        None
+    }
+}
+
+pub(crate) fn gold_representation<R, RES>(
+    input: R,
+    binary: bool,
+    resolver: &RES,
+) -> Result<GoldRepr, Ck3Error>
+where
+    R: Read,
+    RES: TokenResolver,
+{
+    let mut scanner = GoldScanner::default();
+    if binary {
+        let mut reader = binary::TokenReader::new(input);
+        while let Some(token) = reader.next()? {
+            let result = match token {
+                binary::Token::Open => scanner.open(),
+                binary::Token::Close => {
+                    scanner.close();
+                    None
+                }
+                binary::Token::Equal => None,
+                binary::Token::Id(id) => scanner.scalar(resolver.resolve(id)),
+                _ => scanner.scalar(None),
+            };
+
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+    } else {
+        let mut reader = text::TokenReader::new(input);
+        while let Some(token) = reader.next().map_err(|e| Ck3ErrorKind::Parse(e.into()))? {
+            let result = match token {
+                text::Token::Open => scanner.open(),
+                text::Token::Close => {
+                    scanner.close();
+                    None
+                }
+                text::Token::Operator(_) => None,
+                text::Token::Unquoted(x) | text::Token::Quoted(x) => {
+                    scanner.scalar(std::str::from_utf8(x.as_bytes()).ok())
+                }
+            };
+
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+    }
+
+    Ok(GoldRepr::Unknown)
+}
+
+/// A binary integer that the melter would decode as a date, but the
+/// resulting year is not plausible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateAnomaly {
+    /// Dot separated keys leading to the value (eg: `living.16322.birth`)
+    pub path: String,
+
+    /// The raw integer as found in the save
+    pub raw: i32,
+
+    /// The date the integer decodes to
+    pub date: Ck3Date,
+}
+
+/// Maintains the path of keys to the current position of a binary token
+/// stream
+#[derive(Debug, Default)]
+struct KeyPath {
+    keys: Vec<String>,
+    last: Option<String>,
+    pending: Option<String>,
+    after_equal: bool,
+}
+
+impl KeyPath {
+    fn open(&mut self) {
+        let key = if self.after_equal {
+            self.pending.take()
+        } else {
+            None
+        };
+
+        self.keys.push(key.unwrap_or_else(|| String::from("-")));
+        self.last = None;
+        self.after_equal = false;
+    }
+
+    fn close(&mut self) {
+        let _ = self.keys.pop();
+        self.last = None;
+        self.after_equal = false;
+    }
+
+    fn equal(&mut self) {
+        self.pending = self.last.take();
+        self.after_equal = true;
+    }
+
+    fn scalar(&mut self, name: String) {
+        if self.after_equal {
+            self.after_equal = false;
+            self.pending = None;
+        } else {
+            self.last = Some(name);
+        }
+    }
+
+    /// The key that the next scalar is the value of
+    fn value_key(&self) -> Option<&str> {
+        self.pending.as_deref().filter(|_| self.after_equal)
+    }
+
+    fn path(&self, leaf: &str) -> String {
+        let mut result = self.keys.join(".");
+        if !result.is_empty() {
+            result.push('.');
+        }
+        result.push_str(leaf);
+        result
+    }
+}
+
+fn is_plausible_year(year: i16) -> bool {
+    year > 0 && year <= 10000
+}
+
+pub(crate) fn validate_dates<R, RES>(input: R, resolver: &RES) -> Result<Vec<DateAnomaly>, Ck3Error>
+where
+    R: Read,
+    RES: TokenResolver,
+{
+    let mut anomalies = Vec::new();
+    let mut path = KeyPath::default();
+    let mut reader = binary::TokenReader::new(input);
+    while let Some(token) = reader.next()? {
+        match token {
+            binary::Token::Open => path.open(),
+            binary::Token::Close => path.close(),
+            binary::Token::Equal => path.equal(),
+            binary::Token::I32(x) => {
+                let key = path.value_key();
+                let known_number = matches!(key, Some("seed" | "random_count"));
+                let date = if key == Some("birth") {
+                    crate::decode_date(x)
+                } else {
+                    crate::decode_date_heuristic(x)
+                };
+
+                match date {
+                    Some(date) if !known_number && !is_plausible_year(date.year()) => {
+                        anomalies.push(DateAnomaly {
+                            path: path.path(key.unwrap_or("-")),
+                            raw: x,
+                            date,
+                        });
+                    }
+                    _ => {}
+                }
+
+                path.scalar(x.to_string());
+            }
+            binary::Token::Id(x) => match resolver.resolve(x) {
+                Some(id) => path.scalar(String::from(id)),
+                None => path.scalar(format!("__unknown_0x{:x}", x)),
+            },
+            binary::Token::Quoted(x) | binary::Token::Unquoted(x) => path.scalar(x.to_string()),
+            binary::Token::U32(x) => path.scalar(x.to_string()),
+            binary::Token::U64(x) => path.scalar(x.to_string()),
+            binary::Token::I64(x) => path.scalar(x.to_string()),
+            _ => path.scalar(String::new()),
+        }
+    }
+
+    Ok(anomalies)
+}
+
+/// Aggregate figures of a save's gamestate
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaveStatistics {
+    /// The number of characters under `living`
+    pub living_characters: usize,
+
+    /// The number of characters under `dead_unprunable` and
+    /// `characters.dead_prunable`
+    pub dead_characters: usize,
+
+    /// The number of landed titles
+    pub titles: usize,
+
+    /// The number of active wars
+    pub wars: usize,
+
+    /// The sum of gold across living characters
+    pub total_gold: f64,
+}
+
+impl SaveStatistics {
+    /// Counts the object that is about to be opened within the current path
+    fn open(&mut self, path: &KeyPath) {
+        match path.keys.as_slice() {
+            [a] if a == "living" => self.living_characters += 1,
+            [a] if a == "dead_unprunable" => self.dead_characters += 1,
+            [a, b] if a == "characters" && b == "dead_prunable" => self.dead_characters += 1,
+            [a, b] if a == "landed_titles" && b == "landed_titles" => self.titles += 1,
+            [a, b] if a == "wars" && b == "active_wars" => self.wars += 1,
+            _ => {}
+        }
+    }
+
+    /// Accumulates the value if it is a living character's gold, which is
+    /// either a bare number or (1.16+) the `value` of an object. The value
+    /// must already be decoded, so binary floats need to be reencoded by the
+    /// caller where the flavor requires it.
+    fn number(&mut self, path: &KeyPath, value: impl FnOnce() -> Option<f64>) {
+        let is_gold = match (path.keys.as_slice(), path.value_key()) {
+            ([a, _, b], Some("gold")) => a == "living" && b == "alive_data",
+            ([a, _, b, c], Some("value")) => a == "living" && b == "alive_data" && c == "gold",
+            _ => false,
+        };
+
+        if let Some(x) = value().filter(|_| is_gold) {
+            self.total_gold += x;
+        }
+    }
+}
+
+pub(crate) fn statistics<R, RES>(
+    input: R,
+    binary: bool,
+    resolver: &RES,
+) -> Result<SaveStatistics, Ck3Error>
+where
+    R: Read,
+    RES: TokenResolver,
+{
+    let mut stats = SaveStatistics::default();
+    let mut path = KeyPath::default();
+    if binary {
+        let mut flavor: Box<dyn Ck3BinaryFlavor> = Box::new(Ck3Flavor10::new());
+        let mut reader = binary::TokenReader::new(input);
+        while let Some(token) = reader.next()? {
+            match token {
+                binary::Token::Open => {
+                    stats.open(&path);
+                    path.open();
+                }
+                binary::Token::Close => path.close(),
+                binary::Token::Equal => path.equal(),
+                binary::Token::F64(x) => {
+                    let x = flavor.visit_f64(x);
+                    let reencode = flavor.float_reencoding() && path.value_key() == Some("gold");
+                    stats.number(&path, || Some(if reencode { reencode_float(x) } else { x }));
+                    path.scalar(String::new());
+                }
+                binary::Token::I32(x) => {
+                    if path.value_key() == Some("save_game_version") {
+                        flavor = Ck3FlavorKind::from_save_game_version(x).flavor();
+                    }
+                    path.scalar(x.to_string());
+                }
+                binary::Token::Id(x) => match resolver.resolve(x) {
+                    Some(id) => path.scalar(String::from(id)),
+                    None => path.scalar(format!("__unknown_0x{:x}", x)),
+                },
+                binary::Token::Quoted(x) | binary::Token::Unquoted(x) => path.scalar(x.to_string()),
+                binary::Token::U32(x) => path.scalar(x.to_string()),
+                binary::Token::U64(x) => path.scalar(x.to_string()),
+                binary::Token::I64(x) => path.scalar(x.to_string()),
+                _ => path.scalar(String::new()),
+            }
+        }
+    } else {
+        let mut reader = text::TokenReader::new(input);
+        while let Some(token) = reader.next().map_err(|e| Ck3ErrorKind::Parse(e.into()))? {
+            match token {
+                text::Token::Open => {
+                    stats.open(&path);
+                    path.open();
+                }
+                text::Token::Close => path.close(),
+                text::Token::Operator(_) => path.equal(),
+                text::Token::Unquoted(x) | text::Token::Quoted(x) => {
+                    let value = x.to_string();
+                    stats.number(&path, || value.parse().ok());
+                    path.scalar(value);
+                }
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{binary_save, id, living, resolver};
+    use crate::{Ck3Date, Ck3File, GoldRepr};
+    use jomini::{binary::Token, common::PdsDate};
+
+    /// The living characters with the given gold appended to the last
+    fn living_with_gold(gold: i64) -> Vec<Token<'static>> {
+        let mut tokens = living();
+        tokens.truncate(tokens.len() - 2);
+        tokens.extend_from_slice(&[
+            id("alive_data"),
+            Token::Equal,
+            Token::Open,
+            id("gold"),
+            Token::Equal,
+            Token::F64(gold.to_le_bytes()),
+            Token::Close,
+            Token::Close,
+            Token::Close,
+        ]);
+        tokens
+    }
+
+    #[test]
+    fn test_gold_representation() {
+        let resolver = resolver();
+        let float = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.15.0\" }\nliving={ 1={ alive_data={ health=4.7 gold=133.04397 } } }\n";
+        let file = Ck3File::from_slice(&float[..]).unwrap();
+        assert_eq!(
+            file.gold_representation(&resolver).unwrap(),
+            GoldRepr::Float
+        );
+
+        let object = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.16.0\" }\nliving={ 1={ alive_data={ health=4.7 gold={ value=133.04397 } } } }\n";
+        let file = Ck3File::from_slice(&object[..]).unwrap();
+        assert_eq!(
+            file.gold_representation(&resolver).unwrap(),
+            GoldRepr::Object
+        );
+
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_eq!(
+            file.gold_representation(&resolver).unwrap(),
+            GoldRepr::Unknown
+        );
+
+        let data = binary_save(&living_with_gold(13_304_397));
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_eq!(
+            file.gold_representation(&resolver).unwrap(),
+            GoldRepr::Float
+        );
+    }
+
+    #[test]
+    fn test_validate_dates() {
+        let tokens = vec![
+            id("living"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(1),
+            Token::Equal,
+            Token::Open,
+            id("birth"),
+            Token::Equal,
+            Token::I32(Ck3Date::from_ymd(867, 1, 1).to_binary()),
+            id("dynasty_house"),
+            Token::Equal,
+            Token::I32(43_800_000),
+            Token::Close,
+            Token::Close,
+        ];
+
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let anomalies = file.validate_dates(&resolver()).unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].path, "living.1.dynasty_house");
+        assert_eq!(anomalies[0].raw, 43_800_000);
+        assert_eq!(anomalies[0].date.year(), 0);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let resolver = resolver();
+        let text = br#"SAV0100a40f789f00000000
+meta_data={ version="1.16.0" }
+living={
+    1={ alive_data={ health=4.7 gold=32.768 } }
+    2={ alive_data={ gold={ value=65.536 } } }
+}
+dead_unprunable={ 3={ first_name="Ragnar" } }
+characters={ dead_prunable={ 4={ } 5={ } } }
+landed_titles={ landed_titles={ 0={ key="e_hre" } 1=none 2={ key="k_france" } } }
+wars={ active_wars={ 12={ name="Norse Raid" } 13=none } }
+"#;
+        let file = Ck3File::from_slice(&text[..]).unwrap();
+        let stats = file.statistics(&resolver).unwrap();
+        assert_eq!(stats.living_characters, 2);
+        assert_eq!(stats.dead_characters, 3);
+        assert_eq!(stats.titles, 2);
+        assert_eq!(stats.wars, 1);
+        assert_eq!(stats.total_gold, 98.304);
+
+        let data = binary_save(&living_with_gold(13_304_397));
+        let file = Ck3File::from_slice(&data).unwrap();
+        let stats = file.statistics(&resolver).unwrap();
+        assert_eq!(stats.living_characters, 2);
+        assert_eq!(stats.dead_characters, 0);
+        assert_eq!(stats.total_gold, 406.01797);
+    }
+}
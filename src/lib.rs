@@ -27,21 +27,33 @@ Ironman saves are supported through a provided `TokenResolver`. Per PDS counsel,
 
 */
 
+mod budget;
 mod ck3date;
 mod deflate;
 mod errors;
+mod export;
 mod extraction;
 pub mod file;
 pub(crate) mod flavor;
+mod hash;
 mod header;
+mod inspect;
 mod melt;
 pub mod models;
+mod stream;
+#[cfg(test)]
+mod testing;
 
+pub use budget::TokenBudget;
 pub use ck3date::*;
 pub use errors::*;
+pub use export::characters_to_csv;
 pub use extraction::*;
 #[doc(inline)]
-pub use file::Ck3File;
+pub use file::{deserialize_header, Ck3File};
+pub use flavor::{Ck3BinaryFlavor, Ck3Flavor10, Ck3Flavor15, Ck3FlavorKind};
 pub use header::*;
+pub use inspect::{DateAnomaly, GoldRepr, SaveStatistics};
 pub use jomini::binary::{BasicTokenResolver, FailedResolveStrategy};
 pub use melt::*;
+pub use stream::{Ck3StreamParser, StreamProgress};
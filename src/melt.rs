@@ -1,16 +1,18 @@
 use crate::{
-    file::Ck3ZipFile,
-    flavor::{reencode_float, Ck3BinaryFlavor, Ck3Flavor10, Ck3Flavor15},
-    Ck3Error, Ck3ErrorKind, Encoding, SaveHeader, SaveHeaderKind,
+    file::{Ck3File, Ck3ZipFile},
+    flavor::{reencode_float, Ck3BinaryFlavor, Ck3Flavor15, Ck3FlavorKind},
+    Ck3DateExt, Ck3Error, Ck3ErrorKind, Encoding, SaveHeader, SaveHeaderKind, TokenBudget,
 };
 use jomini::{
-    binary::{FailedResolveStrategy, Token, TokenReader, TokenResolver},
+    binary::{BinaryFlavor, FailedResolveStrategy, Token, TokenReader, TokenResolver},
     common::PdsDate,
     TextWriterBuilder,
 };
 use std::{
-    collections::HashSet,
-    io::{copy, Cursor, Read, Write},
+    collections::{HashMap, HashSet},
+    io::{copy, BufRead, BufReader, Cursor, Lines, Read, Write},
+    sync::Arc,
+    time::Instant,
 };
 
 /// Output from melting a binary save to plaintext
@@ -30,7 +32,101 @@ impl MeltedDocument {
     }
 }
 
-#[derive(Debug)]
+/// Compression applied to the output of [`melt_compressed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionKind {
+    /// Write the melted save as is
+    None,
+
+    /// Gzip compress the melted save
+    Gzip,
+
+    /// Compress the melted save as a raw deflate stream
+    Deflate,
+}
+
+/// Melt a save directly into a compressed writer
+///
+/// The melter writes its output sequentially, so any compressing writer (eg:
+/// a `flate2::write::GzEncoder` or `zstd::Encoder`) can be handed straight to
+/// [`Ck3Melter::melt`] without first buffering the plaintext save. This is a
+/// convenience that wraps the writer in the requested encoder and finishes
+/// the compressed stream once the melt completes.
+pub fn melt_compressed<W, R>(
+    file: &Ck3File,
+    resolver: &R,
+    options: MeltOptions,
+    writer: W,
+    kind: CompressionKind,
+) -> Result<MeltedDocument, Ck3Error>
+where
+    W: Write,
+    R: TokenResolver,
+{
+    let mut melter = file.melter();
+    melter.options(options);
+    let level = flate2::Compression::default();
+    match kind {
+        CompressionKind::None => melter.melt(writer, resolver),
+        CompressionKind::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, level);
+            let doc = melter.melt(&mut encoder, resolver)?;
+            encoder.finish()?;
+            Ok(doc)
+        }
+        CompressionKind::Deflate => {
+            let mut encoder = flate2::write::DeflateEncoder::new(writer, level);
+            let doc = melter.melt(&mut encoder, resolver)?;
+            encoder.finish()?;
+            Ok(doc)
+        }
+    }
+}
+
+/// Asserts that melting a save does not change how it deserializes
+///
+/// The save is deserialized into a [`Gamestate`](crate::models::Gamestate)
+/// directly and again after it has been melted. The two models are compared
+/// field by field. Intended for use within tests.
+///
+/// # Panics
+///
+/// Panics if the save can't be parsed, melted, or deserialized, or if the
+/// deserialized models differ.
+pub fn assert_melt_deserialize_stable<R>(file: &Ck3File, resolver: &R)
+where
+    R: TokenResolver,
+{
+    use crate::models::Gamestate;
+
+    let mut zip_sink = Vec::new();
+    let parsed = file.parse(&mut zip_sink).expect("save to parse");
+    let direct: Gamestate = parsed
+        .deserializer(resolver)
+        .deserialize()
+        .expect("save to deserialize");
+
+    let mut melted = Vec::new();
+    file.melter()
+        .melt(&mut melted, resolver)
+        .expect("save to melt");
+    let melted_file = Ck3File::from_slice(&melted).expect("melted save to be recognized");
+    let mut zip_sink = Vec::new();
+    let parsed = melted_file
+        .parse(&mut zip_sink)
+        .expect("melted save to parse");
+    let roundtrip: Gamestate = parsed
+        .deserializer(resolver)
+        .deserialize()
+        .expect("melted save to deserialize");
+
+    assert_eq!(
+        direct, roundtrip,
+        "melted save deserializes differently than the original"
+    );
+}
+
+#[derive(Debug, Clone)]
 enum MeltInput<'data> {
     Text(&'data [u8]),
     Binary(&'data [u8]),
@@ -43,10 +139,27 @@ enum MeltInput<'data> {
     },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MeltOptions {
     verbatim: bool,
     on_failed_resolve: FailedResolveStrategy,
+    redact_strings: bool,
+    max_tokens: Option<u64>,
+    deadline: Option<Instant>,
+    quoting: QuotingPolicy,
+    id_map: Option<HashMap<u64, u64>>,
+    flavor: Option<FlavorOverride>,
+    include_characters: Option<HashSet<u64>>,
+    max_array_len: Option<usize>,
+    round_floats: Option<u8>,
+    detect_dates: bool,
+    retain_ironman: bool,
+    comment_unknown_tokens: bool,
+    on_progress: Option<ProgressCallback>,
+    mixed_policy: MixedPolicy,
+    save_id_override: Option<String>,
+    #[cfg(feature = "regex")]
+    key_filter: Option<KeyRegex>,
 }
 
 impl Default for MeltOptions {
@@ -60,10 +173,555 @@ impl MeltOptions {
         Self {
             verbatim: false,
             on_failed_resolve: FailedResolveStrategy::Ignore,
+            redact_strings: false,
+            max_tokens: None,
+            deadline: None,
+            quoting: QuotingPolicy::GameDefault,
+            id_map: None,
+            flavor: None,
+            include_characters: None,
+            max_array_len: None,
+            round_floats: None,
+            detect_dates: true,
+            retain_ironman: false,
+            comment_unknown_tokens: false,
+            on_progress: None,
+            mixed_policy: MixedPolicy::GameDefault,
+            save_id_override: None,
+            #[cfg(feature = "regex")]
+            key_filter: None,
+        }
+    }
+
+    /// Retain ironman and other extraneous data that the game doesn't
+    /// emit in plaintext saves
+    pub fn verbatim(self, verbatim: bool) -> Self {
+        MeltOptions { verbatim, ..self }
+    }
+
+    /// Retain the `ironman` and `ironman_manager` blocks, which are omitted
+    /// by default. Unlike [`MeltOptions::verbatim`], this only concerns the
+    /// ironman blocks.
+    pub fn retain_ironman(self, retain_ironman: bool) -> Self {
+        MeltOptions {
+            retain_ironman,
+            ..self
+        }
+    }
+
+    /// Configure what happens when a binary token can't be resolved
+    pub fn on_failed_resolve(self, on_failed_resolve: FailedResolveStrategy) -> Self {
+        MeltOptions {
+            on_failed_resolve,
+            ..self
+        }
+    }
+
+    /// Write unresolved tokens in value position as an empty string followed
+    /// by a `# unknown token 0x...` comment instead of an `__unknown_0x...`
+    /// scalar, so that the melted output stays loadable. Only values of
+    /// `key=value` pairs are commented, unresolved tokens elsewhere (eg: array
+    /// elements) are still written as `__unknown_0x...` as a comment would
+    /// swallow the rest of the line
+    pub fn comment_unknown_tokens(self, comment_unknown_tokens: bool) -> Self {
+        MeltOptions {
+            comment_unknown_tokens,
+            ..self
+        }
+    }
+
+    /// Replace every string value with a fixed placeholder so that the
+    /// structure of a save can be shared without leaking custom names. Keys
+    /// and numbers are left untouched.
+    pub fn redact_strings(self, redact_strings: bool) -> Self {
+        MeltOptions {
+            redact_strings,
+            ..self
+        }
+    }
+
+    /// Abort the melt with [`Ck3ErrorKind::BudgetExceeded`] after the given
+    /// number of binary tokens have been read, counting both the metadata and
+    /// the gamestate
+    pub fn token_budget(self, max_tokens: u64) -> Self {
+        MeltOptions {
+            max_tokens: Some(max_tokens),
+            ..self
+        }
+    }
+
+    /// Abort the melt with [`Ck3ErrorKind::BudgetExceeded`] once the deadline
+    /// has passed. The deadline is checked periodically, so the melt may
+    /// overshoot it slightly.
+    pub fn deadline(self, deadline: Instant) -> Self {
+        MeltOptions {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Configure how string values are quoted in the melted output
+    pub fn quoting_policy(self, quoting: QuotingPolicy) -> Self {
+        MeltOptions { quoting, ..self }
+    }
+
+    /// Configure how containers that mix array values with key value pairs
+    /// are written in the melted output
+    pub fn mixed_container_policy(self, mixed_policy: MixedPolicy) -> Self {
+        MeltOptions {
+            mixed_policy,
+            ..self
+        }
+    }
+
+    /// Rewrite character ids through the given map, so that a save can be
+    /// anonymized while staying loadable. Only the keys of the `living`
+    /// object and the values of known character fields (eg: `liege`,
+    /// `holder`) are rewritten, as other integers are not necessarily ids.
+    /// Ids absent from the map are left as is.
+    pub fn with_id_map(self, id_map: HashMap<u64, u64>) -> Self {
+        MeltOptions {
+            id_map: Some(id_map),
+            ..self
+        }
+    }
+
+    /// Only emit the living characters with the given ids. All other
+    /// characters within the `living` object are omitted.
+    pub fn include_characters(self, ids: HashSet<u64>) -> Self {
+        MeltOptions {
+            include_characters: Some(ids),
+            ..self
+        }
+    }
+
+    /// Truncate arrays after the given number of elements, noting the
+    /// truncation with a `# truncated` comment. An array can't be told apart
+    /// from an object until its second element, so at least one element is
+    /// always retained.
+    pub fn max_array_len(self, max_array_len: usize) -> Self {
+        MeltOptions {
+            max_array_len: Some(max_array_len),
+            ..self
+        }
+    }
+
+    /// Write every float with exactly the given number of decimals, regardless
+    /// of the precision the float would otherwise be written with. Useful for
+    /// removing float jitter when diffing melted saves.
+    pub fn round_floats(self, round_floats: Option<u8>) -> Self {
+        MeltOptions {
+            round_floats,
+            ..self
+        }
+    }
+
+    /// Whether integers of fields that aren't known to be dates are written
+    /// as dates when they look like one (the default). Disable when the
+    /// heuristic mistakes large integers (eg: seeds) in a save for dates.
+    pub fn detect_dates(self, detect_dates: bool) -> Self {
+        MeltOptions {
+            detect_dates,
+            ..self
+        }
+    }
+
+    /// Decode the save with the given binary flavor instead of the one
+    /// detected from the save's version. Intended for experimenting with how
+    /// a new patch encodes its data.
+    pub fn with_flavor(self, flavor: Box<dyn Ck3BinaryFlavor>) -> Self {
+        MeltOptions {
+            flavor: Some(FlavorOverride(Arc::from(flavor))),
+            ..self
+        }
+    }
+
+    /// Periodically invoke the callback with the number of bytes of binary
+    /// data consumed so far, so that progress can be reported when melting
+    /// large saves
+    pub fn on_progress<F>(self, callback: F) -> Self
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        MeltOptions {
+            on_progress: Some(ProgressCallback(Arc::new(callback))),
+            ..self
+        }
+    }
+
+    /// Replace the save id line (which includes a checksum) with the given
+    /// line in the melted output
+    pub fn save_id_override(self, save_id_override: Option<String>) -> Self {
+        MeltOptions {
+            save_id_override,
+            ..self
+        }
+    }
+
+    /// Only emit the key value lines whose key matches the regex. The
+    /// structure of the save is flattened so that each emitted line is
+    /// prefixed with the dot separated path of the objects that contain it
+    /// (eg: `living.16322.alive_data.gold=133.04397`). Anonymous objects
+    /// within an array are identified by their index.
+    #[cfg(feature = "regex")]
+    pub fn key_filter(self, regex: regex::Regex) -> Self {
+        MeltOptions {
+            key_filter: Some(KeyRegex(regex)),
+            ..self
+        }
+    }
+}
+
+impl MeltOptions {
+    /// The budget shared by every part of a single melt
+    fn budget(&self) -> TokenBudget {
+        let budget = TokenBudget::new();
+        let budget = match self.max_tokens {
+            Some(max_tokens) => budget.max_tokens(max_tokens),
+            None => budget,
+        };
+        match self.deadline {
+            Some(deadline) => budget.deadline(deadline),
+            None => budget,
+        }
+    }
+
+    /// Writes the save id line of the melted output
+    fn write_save_id<W>(&self, header: &SaveHeader, mut writer: W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        match &self.save_id_override {
+            Some(line) => {
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(b"\n")
+            }
+            None => header.write(writer),
+        }
+    }
+}
+
+/// A user provided progress callback. Options sharing the same callback
+/// instance compare equal.
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(u64) + Send + Sync>);
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProgressCallback").finish()
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+/// A user provided flavor. Options sharing the same flavor instance compare
+/// equal.
+#[derive(Clone)]
+struct FlavorOverride(Arc<dyn Ck3BinaryFlavor>);
+
+impl std::fmt::Debug for FlavorOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FlavorOverride").finish()
+    }
+}
+
+impl PartialEq for FlavorOverride {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for FlavorOverride {}
+
+impl jomini::Encoding for FlavorOverride {
+    fn decode<'a>(&self, data: &'a [u8]) -> std::borrow::Cow<'a, str> {
+        self.0.decode(data)
+    }
+}
+
+impl BinaryFlavor for FlavorOverride {
+    fn visit_f32(&self, data: [u8; 4]) -> f32 {
+        self.0.visit_f32(data)
+    }
+
+    fn visit_f64(&self, data: [u8; 8]) -> f64 {
+        self.0.visit_f64(data)
+    }
+}
+
+impl Ck3BinaryFlavor for FlavorOverride {
+    fn float_reencoding(&self) -> bool {
+        self.0.float_reencoding()
+    }
+
+    fn unquote_token(&self, token: &str) -> bool {
+        self.0.unquote_token(token)
+    }
+}
+
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+struct KeyRegex(regex::Regex);
+
+#[cfg(feature = "regex")]
+impl PartialEq for KeyRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Eq for KeyRegex {}
+
+/// Flattens a save into the key value lines whose key matches the regex,
+/// prefixed with the path of the objects that contain them. Fed one token at
+/// a time.
+#[cfg(feature = "regex")]
+#[derive(Debug)]
+struct KeyPaths {
+    regex: regex::Regex,
+    path: Vec<String>,
+
+    // The number of anonymous objects seen at each depth
+    indices: Vec<usize>,
+
+    // The last scalar seen, which is a key if an `=` follows
+    scalar: String,
+
+    // The key of the upcoming value
+    key: Option<String>,
+}
+
+#[cfg(feature = "regex")]
+impl KeyPaths {
+    fn new(regex: regex::Regex) -> Self {
+        KeyPaths {
+            regex,
+            path: Vec::new(),
+            indices: vec![0],
+            scalar: String::new(),
+            key: None,
+        }
+    }
+
+    /// Records a scalar, returning the flattened line when it is the value of
+    /// a matching key
+    fn scalar(&mut self, text: &str) -> Option<String> {
+        let Some(key) = self.key.take() else {
+            self.scalar.clear();
+            self.scalar.push_str(text);
+            return None;
+        };
+
+        if !self.regex.is_match(&key) {
+            return None;
+        }
+
+        let mut result = String::new();
+        for segment in &self.path {
+            result.push_str(segment);
+            result.push('.');
+        }
+        result.push_str(&key);
+        result.push('=');
+        result.push_str(text);
+        Some(result)
+    }
+
+    fn equal(&mut self) {
+        self.key = Some(std::mem::take(&mut self.scalar));
+    }
+
+    fn open(&mut self) {
+        let segment = match (self.key.take(), self.indices.last_mut()) {
+            (Some(key), _) => key,
+            (None, Some(index)) => {
+                *index += 1;
+                (*index - 1).to_string()
+            }
+            (None, None) => String::new(),
+        };
+
+        self.path.push(segment);
+        self.indices.push(0);
+    }
+
+    fn close(&mut self) {
+        self.key = None;
+        if self.path.pop().is_some() {
+            self.indices.pop();
+        }
+    }
+}
+
+/// Key paths of a binary melt. Tokens are melted into a scratch writer so
+/// that keys and values are recorded exactly as they would have been written.
+#[cfg(feature = "regex")]
+#[derive(Debug)]
+struct KeyFilter {
+    paths: KeyPaths,
+    scratch: jomini::TextWriter<Vec<u8>>,
+}
+
+#[cfg(feature = "regex")]
+impl KeyFilter {
+    fn new(regex: regex::Regex) -> Self {
+        KeyFilter {
+            paths: KeyPaths::new(regex),
+            scratch: TextWriterBuilder::new().from_writer(Vec::new()),
+        }
+    }
+
+    /// Records the token that was just melted into the scratch writer, which
+    /// had the given depth beforehand
+    fn record(&mut self, depth: usize, after_equal: bool) -> Option<String> {
+        let new_depth = self.scratch.depth();
+        let written = String::from_utf8_lossy(self.scratch.inner());
+        let text = written.trim();
+        let line = if new_depth > depth {
+            self.paths.open();
+            None
+        } else if new_depth < depth {
+            self.paths.close();
+            None
+        } else if after_equal {
+            // A quoted key at the start of an object is only written once
+            // the `=` that follows it is read
+            let key = text.strip_suffix('=').unwrap_or_default().trim_end();
+            if !key.is_empty() {
+                self.paths.scalar(key);
+            }
+            self.paths.equal();
+            None
+        } else if text.is_empty() {
+            None
+        } else {
+            self.paths.scalar(text)
+        };
+
+        self.scratch.inner().clear();
+        line
+    }
+}
+
+/// Writes the flattened lines of a plaintext save
+#[cfg(feature = "regex")]
+fn filter_text<R, W>(
+    data: R,
+    regex: regex::Regex,
+    mut output: W,
+) -> Result<MeltedDocument, Ck3Error>
+where
+    R: Read,
+    W: Write,
+{
+    let mut reader = jomini::text::TokenReader::new(data);
+    let mut paths = KeyPaths::new(regex);
+    while let Some(line) = next_text_line(&mut reader, &mut paths)? {
+        output.write_all(line.as_bytes())?;
+        output.write_all(b"\n")?;
+    }
+    Ok(MeltedDocument::new())
+}
+
+/// Reads tokens of a plaintext save until the next flattened line
+#[cfg(feature = "regex")]
+fn next_text_line<R>(
+    reader: &mut jomini::text::TokenReader<R>,
+    paths: &mut KeyPaths,
+) -> Result<Option<String>, Ck3Error>
+where
+    R: Read,
+{
+    use jomini::text::Token;
+
+    while let Some(token) = reader.next().map_err(jomini::Error::from)? {
+        let line = match token {
+            Token::Open => {
+                paths.open();
+                None
+            }
+            Token::Close => {
+                paths.close();
+                None
+            }
+            Token::Operator(_) => {
+                paths.equal();
+                None
+            }
+            Token::Unquoted(x) => paths.scalar(&String::from_utf8_lossy(x.as_bytes())),
+            Token::Quoted(x) => {
+                let quoted = format!("\"{}\"", String::from_utf8_lossy(x.as_bytes()));
+                paths.scalar(&quoted)
+            }
+        };
+
+        if line.is_some() {
+            return Ok(line);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Controls which string values are written with quotes when melting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuotingPolicy {
+    /// Quote strings the same way the game does in plaintext saves, which
+    /// leaves a known set of tokens (eg: `color1`) unquoted
+    #[default]
+    GameDefault,
+
+    /// Quote every string the binary save stores as quoted, including those
+    /// the game writes unquoted
+    QuoteAll,
+
+    /// Only quote string values that would not be read back as a single
+    /// unquoted scalar (eg: they are empty or contain whitespace)
+    MinimalQuoting,
+}
+
+/// Controls how containers that mix array values with key value pairs (eg:
+/// `{ 10 0=2 1=2 }`) are melted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MixedPolicy {
+    /// Write the container on a single line, the same as the game does
+    #[default]
+    GameDefault,
+
+    /// Write the container as an object with one key value pair per line
+    /// from the first pair onwards. Array values that precede the first pair
+    /// have already been written by then, so they are left as is.
+    ForceObject,
+}
+
+impl QuotingPolicy {
+    #[inline]
+    fn apply(self, kind: QuoteKind, data: &[u8]) -> QuoteKind {
+        match self {
+            QuotingPolicy::GameDefault => kind,
+            QuotingPolicy::QuoteAll => QuoteKind::Inactive,
+            QuotingPolicy::MinimalQuoting if requires_quotes(data) => QuoteKind::Inactive,
+            QuotingPolicy::MinimalQuoting => QuoteKind::UnquoteAll,
         }
     }
 }
 
+fn requires_quotes(data: &[u8]) -> bool {
+    data.is_empty()
+        || !data
+            .iter()
+            .all(|&x| x.is_ascii_alphanumeric() || matches!(x, b'_' | b'.' | b':' | b'-'))
+}
+
+const REDACTED: &[u8] = b"redacted";
+
 #[derive(Debug, Clone, Copy)]
 enum QuoteKind {
     // Regular quoting rules
@@ -118,6 +776,8 @@ impl Quoter {
 enum Block {
     Alive,
     AiStrategies,
+    Inspirations,
+    Living,
     Inactive,
 }
 
@@ -128,6 +788,7 @@ struct Blocks {
 
     in_ai_strageties: bool,
     in_alive_data: bool,
+    in_inspirations: bool,
 }
 
 impl Blocks {
@@ -142,6 +803,11 @@ impl Blocks {
                 self.in_alive_data = true;
                 Block::Alive
             }
+            Some(Block::Inspirations) => {
+                self.in_inspirations = true;
+                Block::Inspirations
+            }
+            Some(Block::Living) => Block::Living,
             _ => Block::Inactive,
         };
 
@@ -162,6 +828,9 @@ impl Blocks {
             Some(Block::AiStrategies) => {
                 self.in_ai_strageties = false;
             }
+            Some(Block::Inspirations) => {
+                self.in_inspirations = false;
+            }
             _ => {}
         }
     }
@@ -175,6 +844,11 @@ impl Blocks {
     fn at_ai_strategies(&self) -> bool {
         matches!(self.data.last(), Some(Block::AiStrategies))
     }
+
+    #[inline]
+    fn at_living(&self) -> bool {
+        matches!(self.data.last(), Some(Block::Living))
+    }
 }
 
 /// Convert a binary save to plaintext
@@ -231,6 +905,12 @@ impl<'data> Ck3Melter<'data> {
         self
     }
 
+    /// Replace all melt options at once
+    pub fn options(&mut self, options: MeltOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+
     pub fn input_encoding(&self) -> Encoding {
         match &self.input {
             MeltInput::Text(_) => Encoding::Text,
@@ -249,18 +929,29 @@ impl<'data> Ck3Melter<'data> {
         Writer: Write,
         R: TokenResolver,
     {
+        #[cfg(feature = "regex")]
+        if let Some(KeyRegex(regex)) = &self.options.key_filter {
+            match &self.input {
+                MeltInput::Text(x) => return filter_text(*x, regex.clone(), output),
+                MeltInput::ZipText { file, .. } => {
+                    return filter_text(file.reader(), regex.clone(), output)
+                }
+                _ => {}
+            }
+        }
+
         match &mut self.input {
             MeltInput::Text(x) => {
-                self.header.write(&mut output)?;
+                self.options.write_save_id(&self.header, &mut output)?;
                 output.write_all(x)?;
                 Ok(MeltedDocument::new())
             }
-            MeltInput::Binary(x) => melt(x, output, resolver, self.options, self.header.clone()),
+            MeltInput::Binary(x) => melt(x, output, resolver, &self.options, self.header.clone()),
             MeltInput::ZipText { file, metadata_len } => {
                 let mut header = self.header.clone();
                 header.set_kind(SaveHeaderKind::Text);
                 header.set_metadata_len(*metadata_len as u64);
-                header.write(&mut output)?;
+                self.options.write_save_id(&header, &mut output)?;
                 let mut reader = file.reader();
                 copy(&mut reader, &mut output).map_err(Ck3ErrorKind::from)?;
                 Ok(MeltedDocument::new())
@@ -269,79 +960,237 @@ impl<'data> Ck3Melter<'data> {
                 file.reader(),
                 &mut output,
                 resolver,
-                self.options,
+                &self.options,
                 self.header.clone(),
             ),
         }
     }
-}
 
-pub(crate) fn melt<Reader, Writer, Resolver>(
-    input: Reader,
-    mut output: Writer,
-    resolver: Resolver,
-    options: MeltOptions,
-    mut header: SaveHeader,
-) -> Result<MeltedDocument, Ck3Error>
-where
-    Reader: Read,
-    Writer: Write,
-    Resolver: TokenResolver,
-{
-    let mut reader = TokenReader::new(input);
+    /// Lazily melt the input, yielding one line at a time (without the
+    /// line terminator).
+    ///
+    /// Only the save id line and the metadata section are buffered upfront,
+    /// so memory usage stays bounded even for the largest gamestates.
+    pub fn melt_lines<'res, R>(&self, resolver: &'res R) -> MeltLines<'data, 'res, R>
+    where
+        R: TokenResolver,
+    {
+        let (reader, binary): (Box<dyn Read + 'data>, bool) = match &self.input {
+            MeltInput::Text(x) => (Box::new(*x), false),
+            MeltInput::Binary(x) => (Box::new(*x), true),
+            MeltInput::ZipText { file, .. } => (Box::new(file.reader()), false),
+            MeltInput::ZipBinary { file } => (Box::new(file.reader()), true),
+        };
 
-    let header_sink = Vec::new();
-    let mut wtr = TextWriterBuilder::new()
-        .indent_char(b'\t')
-        .indent_factor(1)
-        .from_writer(Cursor::new(header_sink));
+        #[cfg(feature = "regex")]
+        if let (false, Some(KeyRegex(regex))) = (binary, &self.options.key_filter) {
+            return MeltLines {
+                kind: MeltLinesKind::FilteredText {
+                    reader: jomini::text::TokenReader::new(reader),
+                    paths: KeyPaths::new(regex.clone()),
+                },
+                resolver,
+                options: self.options.clone(),
+            };
+        }
 
-    let err = || Ck3Error::from(Ck3ErrorKind::InvalidHeader);
-    match reader.next()?.ok_or_else(err)? {
-        Token::Id(id) => match resolver.resolve(id) {
-            Some(name) => wtr.write_unquoted(name.as_bytes())?,
-            None => return Err(err()),
-        },
-        _ => return Err(err()),
-    };
+        let kind = if binary {
+            MeltLinesKind::Binary(Box::new(BinaryLines {
+                reader: TokenReader::new(reader),
+                wtr: TextWriterBuilder::new()
+                    .indent_char(b'\t')
+                    .indent_factor(1)
+                    .from_writer(Vec::new()),
+                flavor: None,
+                state: MeltState::new(false, self.options.budget(), &self.options),
+                unknown_tokens: HashSet::new(),
+                header: Some(self.header.clone()),
+                done: false,
+            }))
+        } else {
+            let mut header = self.header.clone();
+            if let MeltInput::ZipText { metadata_len, .. } = &self.input {
+                header.set_kind(SaveHeaderKind::Text);
+                header.set_metadata_len(*metadata_len as u64);
+            }
 
-    match reader.next()?.ok_or_else(err)? {
-        Token::Equal => wtr.write_operator(jomini::text::Operator::Equal)?,
-        _ => return Err(err()),
-    };
+            let mut header_line = Vec::new();
+            let _ = self.options.write_save_id(&header, &mut header_line);
+            MeltLinesKind::Text {
+                header: Some(header_line),
+                lines: BufReader::new(reader).lines(),
+            }
+        };
 
-    match reader.next()?.ok_or_else(err)? {
-        Token::Open => wtr.write_object_start()?,
-        _ => return Err(err()),
-    };
+        MeltLines {
+            kind,
+            resolver,
+            options: self.options.clone(),
+        }
+    }
+}
 
-    match reader.next()?.ok_or_else(err)? {
-        Token::Id(id) => match resolver.resolve(id) {
-            Some(name) => wtr.write_unquoted(name.as_bytes())?,
-            None => return Err(err()),
-        },
-        _ => return Err(err()),
-    };
+/// An iterator over the lines of a melted save
+///
+/// Created with [`Ck3Melter::melt_lines`]
+pub struct MeltLines<'data, 'res, R> {
+    kind: MeltLinesKind<'data>,
+    resolver: &'res R,
+    options: MeltOptions,
+}
 
-    match reader.next()?.ok_or_else(err)? {
-        Token::Equal => wtr.write_operator(jomini::text::Operator::Equal)?,
-        _ => return Err(err()),
-    };
+enum MeltLinesKind<'data> {
+    Text {
+        header: Option<Vec<u8>>,
+        lines: Lines<BufReader<Box<dyn Read + 'data>>>,
+    },
+    Binary(Box<BinaryLines<'data>>),
+    #[cfg(feature = "regex")]
+    FilteredText {
+        reader: jomini::text::TokenReader<Box<dyn Read + 'data>>,
+        paths: KeyPaths,
+    },
+}
 
-    let version = match reader.next()?.ok_or_else(err)? {
-        Token::I32(version) => version,
-        _ => return Err(err()),
-    };
+struct BinaryLines<'data> {
+    reader: TokenReader<Box<dyn Read + 'data>>,
+    wtr: jomini::TextWriter<Vec<u8>>,
+    flavor: Option<Box<dyn Ck3BinaryFlavor>>,
+    state: MeltState,
+    unknown_tokens: HashSet<u16>,
+    header: Option<SaveHeader>,
+    done: bool,
+}
 
-    wtr.write_i32(version)?;
+impl<'data> BinaryLines<'data> {
+    fn next_line<R>(
+        &mut self,
+        resolver: &R,
+        options: &MeltOptions,
+    ) -> Result<Option<String>, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        if let Some(header) = self.header.take() {
+            let budget = self.state.budget.clone();
+            let melted = melt_header(&mut self.reader, resolver, options, header, &budget)?;
+            self.wtr.inner().extend_from_slice(&melted.data);
+            self.flavor = Some(melted.flavor);
+            self.unknown_tokens = melted.unknown_tokens;
+        }
 
-    let flavor: Box<dyn Ck3BinaryFlavor> = if version > 5 {
-        Box::new(Ck3Flavor15::new())
-    } else {
-        Box::new(Ck3Flavor10::new())
-    };
+        loop {
+            let buf = self.wtr.inner();
+            if let Some(pos) = memchr_newline(buf) {
+                let rest = buf.split_off(pos + 1);
+                let mut line = std::mem::replace(buf, rest);
+                line.pop();
+                return utf8_to_string(line).map(Some);
+            }
 
-    let mut unknown_tokens = HashSet::new();
+            if self.done {
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+                return utf8_to_string(std::mem::take(buf)).map(Some);
+            }
+
+            let flavor = self.flavor.as_deref().ok_or(Ck3ErrorKind::InvalidHeader)?;
+            let step = self.state.melt_token(
+                &mut self.reader,
+                &mut self.wtr,
+                flavor,
+                resolver,
+                options,
+                &mut self.unknown_tokens,
+            )?;
+
+            if step == MeltStep::Eof {
+                self.state.finish(&mut self.wtr)?;
+                self.done = true;
+            }
+        }
+    }
+}
+
+fn memchr_newline(data: &[u8]) -> Option<usize> {
+    data.iter().position(|&x| x == b'\n')
+}
+
+pub(crate) fn utf8_to_string(data: Vec<u8>) -> Result<String, Ck3Error> {
+    String::from_utf8(data)
+        .map_err(|e| Ck3Error::from(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+impl<'data, 'res, R> Iterator for MeltLines<'data, 'res, R>
+where
+    R: TokenResolver,
+{
+    type Item = Result<String, Ck3Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.kind.next_line(self.resolver, &self.options)
+    }
+}
+
+impl<'data> MeltLinesKind<'data> {
+    fn next_line<R>(
+        &mut self,
+        resolver: &R,
+        options: &MeltOptions,
+    ) -> Option<Result<String, Ck3Error>>
+    where
+        R: TokenResolver,
+    {
+        match self {
+            MeltLinesKind::Text { header, lines } => match header.take() {
+                Some(mut line) => {
+                    line.pop();
+                    Some(utf8_to_string(line))
+                }
+                None => lines.next().map(|x| x.map_err(Ck3Error::from)),
+            },
+            MeltLinesKind::Binary(lines) => {
+                let result = lines.next_line(resolver, options);
+                if result.is_err() {
+                    lines.done = true;
+                    lines.wtr.inner().clear();
+                }
+                result.transpose()
+            }
+            #[cfg(feature = "regex")]
+            MeltLinesKind::FilteredText { reader, paths } => {
+                next_text_line(reader, paths).transpose()
+            }
+        }
+    }
+}
+
+pub(crate) fn melt<Reader, Writer, Resolver>(
+    input: Reader,
+    mut output: Writer,
+    resolver: Resolver,
+    options: &MeltOptions,
+    header: SaveHeader,
+) -> Result<MeltedDocument, Ck3Error>
+where
+    Reader: Read,
+    Writer: Write,
+    Resolver: TokenResolver,
+{
+    let mut reader = TokenReader::new(input);
+    let budget = options.budget();
+    let MeltedHeader {
+        data,
+        flavor,
+        mut unknown_tokens,
+    } = melt_header(&mut reader, &resolver, options, header, &budget)?;
+    output.write_all(&data)?;
+
+    let mut wtr = TextWriterBuilder::new()
+        .indent_char(b'\t')
+        .indent_factor(1)
+        .from_writer(output);
 
     inner_melt(
         &mut reader,
@@ -350,33 +1199,111 @@ where
         &resolver,
         options,
         &mut unknown_tokens,
-        true,
+        MeltState::new(false, budget, options),
     )?;
 
-    let mut data = wtr.into_inner().into_inner();
-    data.push(b'\n');
-    header.set_kind(SaveHeaderKind::Text);
-    header.set_metadata_len(data.len() as u64);
+    Ok(MeltedDocument { unknown_tokens })
+}
 
-    header.write(&mut output)?;
-    output.write_all(&data)?;
+/// The save id line and metadata section of a melted save
+struct MeltedHeader {
+    data: Vec<u8>,
+    flavor: Box<dyn Ck3BinaryFlavor>,
+    unknown_tokens: HashSet<u16>,
+}
 
+fn melt_header<Reader, Resolver>(
+    reader: &mut TokenReader<Reader>,
+    resolver: Resolver,
+    options: &MeltOptions,
+    mut header: SaveHeader,
+    budget: &TokenBudget,
+) -> Result<MeltedHeader, Ck3Error>
+where
+    Reader: Read,
+    Resolver: TokenResolver,
+{
+    let header_sink = Vec::new();
     let mut wtr = TextWriterBuilder::new()
         .indent_char(b'\t')
         .indent_factor(1)
-        .from_writer(output);
+        .from_writer(Cursor::new(header_sink));
+
+    let err = || Ck3Error::from(Ck3ErrorKind::InvalidHeader);
+    let metadata_key = match reader.next()?.ok_or_else(err)? {
+        Token::Id(id) => resolver.resolve(id).ok_or_else(err)?,
+        _ => return Err(err()),
+    };
+
+    match reader.next()?.ok_or_else(err)? {
+        Token::Equal => {}
+        _ => return Err(err()),
+    };
+
+    match reader.next()?.ok_or_else(err)? {
+        Token::Open => {}
+        _ => return Err(err()),
+    };
+
+    let version_key = match reader.next()?.ok_or_else(err)? {
+        Token::Id(id) => resolver.resolve(id).ok_or_else(err)?,
+        _ => return Err(err()),
+    };
+
+    match reader.next()?.ok_or_else(err)? {
+        Token::Equal => {}
+        _ => return Err(err()),
+    };
+
+    let version = match reader.next()?.ok_or_else(err)? {
+        Token::I32(version) => version,
+        _ => return Err(err()),
+    };
+
+    let flavor: Box<dyn Ck3BinaryFlavor> = match &options.flavor {
+        Some(flavor) => Box::new(flavor.clone()),
+        None => Ck3FlavorKind::from_save_game_version(version).flavor(),
+    };
+
+    let mut state = MeltState::new(true, budget.clone(), options);
+    state.write_metadata_start(&mut wtr, metadata_key, version_key, version)?;
+
+    let mut unknown_tokens = HashSet::new();
 
     inner_melt(
-        &mut reader,
+        reader,
         &mut wtr,
         &flavor,
         &resolver,
         options,
         &mut unknown_tokens,
-        false,
+        state,
     )?;
 
-    Ok(MeltedDocument { unknown_tokens })
+    // Only the flattened lines are kept when filtering keys
+    #[cfg(feature = "regex")]
+    if options.key_filter.is_some() {
+        return Ok(MeltedHeader {
+            data: wtr.into_inner().into_inner(),
+            flavor,
+            unknown_tokens,
+        });
+    }
+
+    let mut metadata = wtr.into_inner().into_inner();
+    metadata.push(b'\n');
+    header.set_kind(SaveHeaderKind::Text);
+    header.set_metadata_len(metadata.len() as u64);
+
+    let mut data = Vec::with_capacity(header.header_len() + metadata.len());
+    options.write_save_id(&header, &mut data)?;
+    data.extend_from_slice(&metadata);
+
+    Ok(MeltedHeader {
+        data,
+        flavor,
+        unknown_tokens,
+    })
 }
 
 fn inner_melt<Reader, Writer, Resolver>(
@@ -384,83 +1311,444 @@ fn inner_melt<Reader, Writer, Resolver>(
     wtr: &mut jomini::TextWriter<Writer>,
     flavor: &dyn Ck3BinaryFlavor,
     resolver: Resolver,
-    options: MeltOptions,
+    options: &MeltOptions,
     unknown_tokens: &mut HashSet<u16>,
-    header: bool,
+    mut state: MeltState,
 ) -> Result<(), Ck3Error>
 where
     Reader: Read,
     Writer: Write,
     Resolver: TokenResolver,
 {
-    let mut reencode_float_token = false;
-    let mut known_number = false;
-    let mut known_date = false;
-    let mut quoted_buffer_enabled = false;
-    let mut quoted_buffer: Vec<u8> = Vec::new();
-    let mut quoter = Quoter::default();
-    let mut block = Blocks::default();
-
-    let mut has_read = false;
-    while let Some(token) = reader.next()? {
-        has_read = true;
-        if quoted_buffer_enabled {
+    loop {
+        match state.melt_token(reader, wtr, flavor, &resolver, options, unknown_tokens)? {
+            MeltStep::Continue => {}
+            MeltStep::HeaderEnd => return Ok(()),
+            MeltStep::Eof => break,
+        }
+    }
+
+    state.finish(wtr)
+}
+
+/// The outcome of melting a single token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MeltStep {
+    Continue,
+    HeaderEnd,
+    Eof,
+}
+
+/// The state of an in progress melt so that melting can be driven one token
+/// at a time
+#[derive(Debug, Default)]
+struct MeltState {
+    reencode_float_token: bool,
+    known_number: bool,
+    known_date: bool,
+    known_id: bool,
+    quoted_buffer_enabled: bool,
+    quoted_buffer: Vec<u8>,
+
+    // Whether the buffered scalar is written unquoted when it is a value
+    unquoted_buffer: bool,
+    quoter: Quoter,
+    block: Blocks,
+    array_lens: Vec<usize>,
+    after_equal: bool,
+    has_read: bool,
+    header: bool,
+    tokens: u64,
+    budget: TokenBudget,
+    #[cfg(feature = "regex")]
+    key_filter: Option<Box<KeyFilter>>,
+}
+
+/// Writes the start of the metadata up to the save game version, which is
+/// read ahead of melting to detect the flavor
+fn write_metadata_start<Writer>(
+    wtr: &mut jomini::TextWriter<Writer>,
+    metadata_key: &str,
+    version_key: &str,
+    version: i32,
+) -> Result<(), Ck3Error>
+where
+    Writer: Write,
+{
+    wtr.write_unquoted(metadata_key.as_bytes())?;
+    wtr.write_operator(jomini::text::Operator::Equal)?;
+    wtr.write_object_start()?;
+    wtr.write_unquoted(version_key.as_bytes())?;
+    wtr.write_operator(jomini::text::Operator::Equal)?;
+    wtr.write_i32(version)?;
+    Ok(())
+}
+
+impl MeltState {
+    #[cfg_attr(not(feature = "regex"), allow(unused_variables))]
+    fn new(header: bool, budget: TokenBudget, options: &MeltOptions) -> Self {
+        MeltState {
+            header,
+            budget,
+            #[cfg(feature = "regex")]
+            key_filter: options
+                .key_filter
+                .as_ref()
+                .map(|KeyRegex(regex)| Box::new(KeyFilter::new(regex.clone()))),
+            ..MeltState::default()
+        }
+    }
+
+    fn write_metadata_start<Writer>(
+        &mut self,
+        wtr: &mut jomini::TextWriter<Writer>,
+        metadata_key: &str,
+        version_key: &str,
+        version: i32,
+    ) -> Result<(), Ck3Error>
+    where
+        Writer: Write,
+    {
+        #[cfg(feature = "regex")]
+        if let Some(filter) = self.key_filter.as_mut() {
+            write_metadata_start(&mut filter.scratch, metadata_key, version_key, version)?;
+            filter.scratch.inner().clear();
+            let paths = &mut filter.paths;
+            paths.scalar(metadata_key);
+            paths.equal();
+            paths.open();
+            paths.scalar(version_key);
+            paths.equal();
+            if let Some(line) = paths.scalar(&version.to_string()) {
+                wtr.inner().write_all(line.as_bytes())?;
+                wtr.inner().write_all(b"\n")?;
+            }
+            return Ok(());
+        }
+
+        write_metadata_start(wtr, metadata_key, version_key, version)
+    }
+
+    /// Melts the next token. When filtering keys, the token is melted into
+    /// a scratch writer and only the flattened lines of matching keys are
+    /// written to the underlying output.
+    fn melt_token<Reader, Writer, Resolver>(
+        &mut self,
+        reader: &mut TokenReader<Reader>,
+        wtr: &mut jomini::TextWriter<Writer>,
+        flavor: &dyn Ck3BinaryFlavor,
+        resolver: &Resolver,
+        options: &MeltOptions,
+        unknown_tokens: &mut HashSet<u16>,
+    ) -> Result<MeltStep, Ck3Error>
+    where
+        Reader: Read,
+        Writer: Write,
+        Resolver: TokenResolver,
+    {
+        #[cfg(feature = "regex")]
+        if let Some(mut filter) = self.key_filter.take() {
+            let depth = filter.scratch.depth();
+            let result = self.step(
+                reader,
+                &mut filter.scratch,
+                flavor,
+                resolver,
+                options,
+                unknown_tokens,
+            );
+            let line = filter.record(depth, self.after_equal);
+            self.key_filter = Some(filter);
+            if let Some(line) = line.filter(|_| result.is_ok()) {
+                wtr.inner().write_all(line.as_bytes())?;
+                wtr.inner().write_all(b"\n")?;
+            }
+            return result;
+        }
+
+        self.step(reader, wtr, flavor, resolver, options, unknown_tokens)
+    }
+
+    fn finish<Writer>(&self, wtr: &mut jomini::TextWriter<Writer>) -> Result<(), Ck3Error>
+    where
+        Writer: Write,
+    {
+        #[cfg(feature = "regex")]
+        if self.key_filter.is_some() {
+            return Ok(());
+        }
+
+        if self.has_read {
+            wtr.inner().write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Defers writing an unquoted scalar at the start of a container until
+    /// the next token reveals whether it is a key (kept) or a value (redacted)
+    #[inline]
+    fn buffer_unquoted(&mut self, data: &[u8]) {
+        self.quoted_buffer_enabled = true;
+        self.unquoted_buffer = true;
+        self.quoted_buffer.extend_from_slice(data);
+    }
+
+    /// The replacement for an integer if it is a character id that the
+    /// options remap
+    #[inline]
+    fn mapped_id<Writer>(
+        &self,
+        wtr: &jomini::TextWriter<Writer>,
+        options: &MeltOptions,
+        known_id: bool,
+        x: u64,
+    ) -> Option<u64>
+    where
+        Writer: Write,
+    {
+        let id_map = options.id_map.as_ref()?;
+        let at_key = wtr.expecting_key() || wtr.at_unknown_start();
+        if known_id || (self.block.at_living() && at_key) {
+            id_map.get(&x).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Whether the integer is the key of a living character that the options
+    /// exclude
+    #[inline]
+    fn is_excluded_character<Writer>(
+        &self,
+        wtr: &jomini::TextWriter<Writer>,
+        options: &MeltOptions,
+        x: u64,
+    ) -> bool
+    where
+        Writer: Write,
+    {
+        let Some(include) = options.include_characters.as_ref() else {
+            return false;
+        };
+
+        let at_key = wtr.expecting_key() || wtr.at_unknown_start();
+        self.block.at_living() && at_key && !include.contains(&x)
+    }
+
+    /// Whether the token is an array element beyond the maximum array length
+    #[inline]
+    fn exceeds_array_len<Writer>(
+        &mut self,
+        wtr: &jomini::TextWriter<Writer>,
+        options: &MeltOptions,
+        token: &Token,
+        after_equal: bool,
+    ) -> bool
+    where
+        Writer: Write,
+    {
+        let Some(max) = options.max_array_len else {
+            return false;
+        };
+
+        if after_equal || matches!(token, Token::Equal | Token::Close) || wtr.expecting_key() {
+            return false;
+        }
+
+        let Some(len) = self.array_lens.last_mut() else {
+            return false;
+        };
+
+        if wtr.at_unknown_start() {
+            *len = 1;
+            false
+        } else if *len >= max.max(1) {
+            true
+        } else {
+            *len += 1;
+            false
+        }
+    }
+
+    fn step<Reader, Writer, Resolver>(
+        &mut self,
+        reader: &mut TokenReader<Reader>,
+        wtr: &mut jomini::TextWriter<Writer>,
+        flavor: &dyn Ck3BinaryFlavor,
+        resolver: &Resolver,
+        options: &MeltOptions,
+        unknown_tokens: &mut HashSet<u16>,
+    ) -> Result<MeltStep, Ck3Error>
+    where
+        Reader: Read,
+        Writer: Write,
+        Resolver: TokenResolver,
+    {
+        if let Some(ProgressCallback(callback)) = &options.on_progress {
+            if self.tokens.is_multiple_of(4096) {
+                callback(reader.position() as u64);
+            }
+        }
+
+        let Some(token) = reader.next()? else {
+            if let Some(ProgressCallback(callback)) = &options.on_progress {
+                callback(reader.position() as u64);
+            }
+            return Ok(MeltStep::Eof);
+        };
+
+        self.tokens += 1;
+        self.budget.spend(1)?;
+
+        self.has_read = true;
+        if self.quoted_buffer_enabled {
+            let value = if options.redact_strings {
+                REDACTED
+            } else {
+                &self.quoted_buffer
+            };
+
             if matches!(token, Token::Equal) {
-                wtr.write_unquoted(&quoted_buffer)?;
+                wtr.write_unquoted(&self.quoted_buffer)?;
+            } else if self.unquoted_buffer {
+                wtr.write_unquoted(value)?;
             } else {
-                wtr.write_quoted(&quoted_buffer)?;
+                wtr.write_quoted(value)?;
             }
-            quoted_buffer.clear();
-            quoted_buffer_enabled = false;
+            self.quoted_buffer.clear();
+            self.quoted_buffer_enabled = false;
+            self.unquoted_buffer = false;
+        }
+
+        let after_equal = std::mem::replace(&mut self.after_equal, matches!(token, Token::Equal));
+
+        // A known id field only applies to the value directly following it
+        let known_id = if matches!(token, Token::Equal) {
+            self.known_id
+        } else {
+            std::mem::take(&mut self.known_id)
+        };
+
+        if self.exceeds_array_len(wtr, options, &token, after_equal) {
+            if matches!(token, Token::Open) {
+                reader.skip_container()?;
+            }
+            reader.skip_container()?;
+
+            wtr.inner().write_all(b" # truncated")?;
+            self.block.pop();
+            self.quoter.pop();
+            self.array_lens.pop();
+            wtr.write_end()?;
+            if self.header && wtr.depth() == 0 {
+                return Ok(MeltStep::HeaderEnd);
+            }
+            return Ok(MeltStep::Continue);
+        }
+
+        let character = match token {
+            Token::U32(x) => Some(u64::from(x)),
+            Token::U64(x) => Some(x),
+            Token::I32(x) => u64::try_from(x).ok(),
+            Token::I64(x) => u64::try_from(x).ok(),
+            _ => None,
+        };
+
+        if character.is_some_and(|x| self.is_excluded_character(wtr, options, x)) {
+            let mut next = reader.read()?;
+            if matches!(next, Token::Equal) {
+                next = reader.read()?;
+            }
+
+            if matches!(next, Token::Open) {
+                reader.skip_container()?;
+            }
+            return Ok(MeltStep::Continue);
         }
 
         match token {
             Token::Open => {
-                block.push();
-                quoter.push();
+                self.block.push();
+                self.quoter.push();
+                self.array_lens.push(0);
                 wtr.write_start()?
             }
             Token::Close => {
-                block.pop();
-                quoter.pop();
+                self.block.pop();
+                self.quoter.pop();
+                self.array_lens.pop();
                 wtr.write_end()?;
-                if header && wtr.depth() == 0 {
-                    return Ok(());
+                if self.header && wtr.depth() == 0 {
+                    return Ok(MeltStep::HeaderEnd);
                 }
             }
             Token::I32(x) => {
-                if known_number || block.at_ai_strategies() {
+                let mapped = u64::try_from(x)
+                    .ok()
+                    .and_then(|x| self.mapped_id(wtr, options, known_id, x));
+                if let Some(id) = mapped {
+                    wtr.write_u64(id)?;
+                } else if self.known_number || self.block.at_ai_strategies() {
                     wtr.write_i32(x)?;
-                    known_number = false;
-                } else if known_date {
-                    if let Some(date) = crate::Ck3Date::from_binary(x) {
+                    self.known_number = false;
+                } else if self.known_date {
+                    if let Some(date) = crate::decode_date(x) {
                         wtr.write_date(date.game_fmt())?;
                     } else if options.on_failed_resolve != FailedResolveStrategy::Error {
                         wtr.write_i32(x)?;
                     } else {
                         return Err(Ck3Error::new(Ck3ErrorKind::InvalidDate(x)));
                     }
-                    known_date = false;
-                } else if let Some(date) = crate::Ck3Date::from_binary_heuristic(x) {
+                    self.known_date = false;
+                } else if let Some(date) =
+                    crate::decode_date_heuristic(x).filter(|_| options.detect_dates)
+                {
                     wtr.write_date(date.game_fmt())?;
                 } else {
                     wtr.write_i32(x)?;
                 }
             }
-            Token::Quoted(x) => match quoter.take_scalar() {
+            Token::Quoted(x) => match options
+                .quoting
+                .apply(self.quoter.take_scalar(), x.as_bytes())
+            {
                 QuoteKind::Inactive if wtr.at_unknown_start() => {
-                    quoted_buffer_enabled = true;
-                    quoted_buffer.extend_from_slice(x.as_bytes());
+                    self.quoted_buffer_enabled = true;
+                    self.quoted_buffer.extend_from_slice(x.as_bytes());
                 }
                 QuoteKind::Inactive if wtr.expecting_key() => wtr.write_unquoted(x.as_bytes())?,
+                QuoteKind::Inactive if options.redact_strings => wtr.write_quoted(REDACTED)?,
                 QuoteKind::Inactive => wtr.write_quoted(x.as_bytes())?,
+                QuoteKind::UnquoteAll if options.redact_strings && wtr.at_unknown_start() => {
+                    self.buffer_unquoted(x.as_bytes());
+                }
+                QuoteKind::UnquoteAll if options.redact_strings && !wtr.expecting_key() => {
+                    wtr.write_unquoted(REDACTED)?
+                }
                 QuoteKind::UnquoteAll => wtr.write_unquoted(x.as_bytes())?,
             },
+            Token::Unquoted(x) if options.redact_strings && wtr.at_unknown_start() => {
+                self.buffer_unquoted(x.as_bytes());
+            }
+            Token::Unquoted(_) if options.redact_strings && !wtr.expecting_key() => {
+                wtr.write_unquoted(REDACTED)?;
+            }
             Token::Unquoted(x) => {
                 wtr.write_unquoted(x.as_bytes())?;
             }
-            Token::F32(x) => write!(wtr, "{:.6}", flavor.visit_f32(x))?,
-            Token::F64(x) if !reencode_float_token => write!(wtr, "{}", flavor.visit_f64(x))?,
+            Token::F32(x) => match options.round_floats {
+                Some(decimals) => write!(wtr, "{:.*}", usize::from(decimals), flavor.visit_f32(x))?,
+                None => write!(wtr, "{:.6}", flavor.visit_f32(x))?,
+            },
+            Token::F64(x) if options.round_floats.is_some() => {
+                let mut x = flavor.visit_f64(x);
+                if std::mem::take(&mut self.reencode_float_token) {
+                    x = reencode_float(x);
+                }
+                let decimals = usize::from(options.round_floats.unwrap_or_default());
+                write!(wtr, "{:.*}", decimals, x)?;
+            }
+            Token::F64(x) if !self.reencode_float_token => write!(wtr, "{}", flavor.visit_f64(x))?,
             Token::F64(x) => {
                 let x = reencode_float(flavor.visit_f64(x));
                 if x.fract().abs() > 1e-6 {
@@ -468,11 +1756,12 @@ where
                 } else {
                     write!(wtr, "{}", x)?;
                 }
-                reencode_float_token = false;
+                self.reencode_float_token = false;
             }
             Token::Id(x) => match resolver.resolve(x) {
                 Some(id) => {
                     if !options.verbatim
+                        && !options.retain_ironman
                         && matches!(id, "ironman" | "ironman_manager")
                         && wtr.expecting_key()
                     {
@@ -484,33 +1773,40 @@ where
                         if matches!(next, Token::Open) {
                             reader.skip_container()?;
                         }
-                        continue;
+                        return Ok(MeltStep::Continue);
                     }
 
-                    block.clear_queued();
-                    quoter.clear_queued();
+                    self.block.clear_queued();
+                    self.quoter.clear_queued();
 
                     if id == "alive_data" {
-                        block.queue(Block::Alive);
+                        self.block.queue(Block::Alive);
                     }
 
                     if id == "ai_strategies" {
-                        block.queue(Block::AiStrategies);
+                        self.block.queue(Block::AiStrategies);
                     }
 
-                    let is_unquote = matches!(
-                        id,
-                        "settings" | "setting" | "perks" | "ethnicities" | "languages"
-                    ) || id == "perk" && block.in_alive_data
+                    if id == "inspirations_manager" {
+                        self.block.queue(Block::Inspirations);
+                    }
+
+                    if id == "living" {
+                        self.block.queue(Block::Living);
+                    }
+
+                    let is_unquote = is_unquoted_token(id)
+                        || id == "perk" && self.block.in_alive_data
                         || flavor.unquote_token(id);
 
                     if is_unquote {
-                        quoter.queue(QuoteKind::UnquoteAll);
+                        self.quoter.queue(QuoteKind::UnquoteAll);
                     }
 
-                    known_number = id == "seed" || id == "random_count";
-                    known_date = id == "birth";
-                    reencode_float_token = matches!(
+                    self.known_number = is_known_number(id);
+                    self.known_id = is_known_id(id);
+                    self.known_date = is_known_date(id);
+                    self.reencode_float_token = matches!(
                         id,
                         "vassal_power_value"
                             | "budget_war_chest"
@@ -519,14 +1815,20 @@ where
                             | "budget_reserved"
                             | "damage_last_tick"
                     );
-                    reencode_float_token |= block.in_alive_data && id == "gold";
-                    reencode_float_token &= flavor.float_reencoding();
+                    self.reencode_float_token |=
+                        (self.block.in_alive_data || self.block.in_inspirations) && id == "gold";
+                    self.reencode_float_token &= flavor.float_reencoding();
 
                     wtr.write_unquoted(id.as_bytes())?;
                 }
                 None => match options.on_failed_resolve {
                     FailedResolveStrategy::Error => {
-                        return Err(Ck3ErrorKind::UnknownToken { token_id: x }.into());
+                        let offset = reader.position() - 2;
+                        return Err(Ck3ErrorKind::UnknownToken {
+                            token_id: x,
+                            offset: Some(offset),
+                        }
+                        .into());
                     }
                     FailedResolveStrategy::Ignore if wtr.expecting_key() => {
                         let mut next = reader.read()?;
@@ -538,6 +1840,11 @@ where
                             reader.skip_container()?;
                         }
                     }
+                    _ if options.comment_unknown_tokens && after_equal && !wtr.at_array_value() => {
+                        unknown_tokens.insert(x);
+                        wtr.write_quoted(b"")?;
+                        write!(wtr.inner(), " # unknown token 0x{:x}", x)?;
+                    }
                     _ => {
                         unknown_tokens.insert(x);
                         write!(wtr, "__unknown_0x{:x}", x)?;
@@ -545,22 +1852,897 @@ where
                 },
             },
             Token::Equal => {
-                if wtr.at_array_value() {
+                if wtr.at_array_value() && options.mixed_policy == MixedPolicy::GameDefault {
                     wtr.start_mixed_mode();
                 }
 
                 wtr.write_operator(jomini::text::Operator::Equal)?
             }
-            Token::U32(x) => wtr.write_u32(x)?,
-            Token::U64(x) => wtr.write_u64(x)?,
+            Token::U32(x) => match self.mapped_id(wtr, options, known_id, u64::from(x)) {
+                Some(id) => wtr.write_u64(id)?,
+                None => wtr.write_u32(x)?,
+            },
+            Token::U64(x) => match self.mapped_id(wtr, options, known_id, x) {
+                Some(id) => wtr.write_u64(id)?,
+                None => wtr.write_u64(x)?,
+            },
             Token::Bool(x) => wtr.write_bool(x)?,
             Token::Rgb(x) => wtr.write_rgb(&x)?,
-            Token::I64(x) => wtr.write_i64(x)?,
+            Token::I64(x) if self.known_date => {
+                match crate::Ck3Date::from_binary_i64(x) {
+                    Some(date) => wtr.write_date(date.game_fmt())?,
+                    None => wtr.write_i64(x)?,
+                }
+                self.known_date = false;
+            }
+            Token::I64(x) => match u64::try_from(x)
+                .ok()
+                .and_then(|x| self.mapped_id(wtr, options, known_id, x))
+            {
+                Some(id) => wtr.write_u64(id)?,
+                None => wtr.write_i64(x)?,
+            },
         }
+
+        Ok(MeltStep::Continue)
     }
+}
+
+fn is_unquoted_token(id: &str) -> bool {
+    matches!(
+        id,
+        "settings" | "setting" | "perks" | "ethnicities" | "languages"
+    )
+}
+
+fn is_known_number(id: &str) -> bool {
+    id == "seed" || id == "random_count"
+}
+
+fn is_known_date(id: &str) -> bool {
+    id == "birth"
+}
+
+fn is_known_id(id: &str) -> bool {
+    matches!(
+        id,
+        "character"
+            | "liege"
+            | "holder"
+            | "owner"
+            | "sponsor"
+            | "diarch"
+            | "leader"
+            | "claimant"
+            | "attacker"
+            | "defender"
+            | "actor"
+            | "recipient"
+            | "secondary_actor"
+            | "secondary_recipient"
+    )
+}
 
-    if has_read {
-        wtr.inner().write_all(b"\n")?;
+/// A token id resolved to its name alongside hints on how the melter
+/// formats the token and its values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedToken<'a> {
+    /// The name of the token
+    pub name: &'a str,
+
+    /// Quoted string values of the token are written unquoted
+    pub unquoted: bool,
+
+    /// Integer values of the token are never interpreted as dates
+    pub number: bool,
+
+    /// Integer values of the token are always interpreted as dates
+    pub date: bool,
+}
+
+/// Resolve a single binary token id through the resolver and annotate it
+/// with the special case knowledge the melter applies to it (using the latest
+/// binary flavor). Returns `None` if the resolver does not know the token.
+pub fn resolve_token<R>(resolver: &R, id: u16) -> Option<ResolvedToken<'_>>
+where
+    R: TokenResolver,
+{
+    let name = resolver.resolve(id)?;
+    let flavor = Ck3Flavor15::new();
+    Some(ResolvedToken {
+        name,
+        unquoted: is_unquoted_token(name) || flavor.unquote_token(name),
+        number: is_known_number(name),
+        date: is_known_date(name),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::testing::{binary_save, id, living, resolver};
+    use jomini::{Scalar, Utf8Encoding};
+
+    fn melt(file: &Ck3File, options: MeltOptions) -> String {
+        let mut out = Vec::new();
+        file.melter()
+            .options(options)
+            .melt(&mut out, &resolver())
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// The living characters with the given alive data appended to the last
+    fn alive_data(fields: &[Token<'static>]) -> Vec<Token<'static>> {
+        let mut tokens = living();
+        tokens.truncate(tokens.len() - 2);
+        tokens.extend_from_slice(&[id("alive_data"), Token::Equal, Token::Open]);
+        tokens.extend_from_slice(fields);
+        tokens.extend_from_slice(&[Token::Close, Token::Close, Token::Close]);
+        tokens
+    }
+
+    #[test]
+    fn test_melt_lines_binary() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let resolver = resolver();
+        let lines = file
+            .melt_lines(&resolver, MeltOptions::new())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(lines.iter().any(|x| x.contains("first_name=\"Halfdan\"")));
+
+        let expected = melt(&file, MeltOptions::new());
+        assert_eq!(lines.join("\n") + "\n", expected);
+    }
+
+    #[test]
+    fn test_melt_redact_strings() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let out = melt(&file, MeltOptions::new().redact_strings(true));
+        assert!(!out.contains("Ivar"));
+        assert!(!out.contains("Halfdan"));
+        assert!(out.contains("first_name=\"redacted\""));
+        assert!(out.contains("save_game_version=3"));
+    }
+
+    #[test]
+    fn test_melt_redact_strings_first_key() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("name_list"),
+            Token::Equal,
+            Token::Open,
+            Token::Unquoted(Scalar::new(b"norse")),
+            Token::Equal,
+            Token::Unquoted(Scalar::new(b"Ragnar")),
+            Token::Close,
+            id("first_name"),
+            Token::Equal,
+            Token::Open,
+            Token::Quoted(Scalar::new(b"custom")),
+            Token::Equal,
+            Token::Quoted(Scalar::new(b"Lodbrok")),
+            Token::Close,
+            id("traits"),
+            Token::Equal,
+            Token::Open,
+            Token::Unquoted(Scalar::new(b"Bjorn")),
+            Token::Unquoted(Scalar::new(b"Ironside")),
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let options = MeltOptions::new()
+            .redact_strings(true)
+            .quoting_policy(QuotingPolicy::MinimalQuoting);
+        let out = melt(&file, options);
+        assert!(out.contains("norse=redacted"), "{}", out);
+        assert!(out.contains("custom=redacted"), "{}", out);
+        assert!(out.contains("\tredacted redacted\n"), "{}", out);
+        assert!(!out.contains("Ragnar"), "{}", out);
+        assert!(!out.contains("Lodbrok"), "{}", out);
+        assert!(!out.contains("Bjorn"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_token_budget() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let err = file
+            .melter()
+            .options(MeltOptions::new().token_budget(5))
+            .melt(Vec::new(), &resolver())
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        // The metadata and gamestate each fit, but not together
+        let err = file
+            .melter()
+            .options(MeltOptions::new().token_budget(20))
+            .melt(Vec::new(), &resolver())
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        let err = file
+            .melter()
+            .options(MeltOptions::new().token_budget(20))
+            .melt_lines(&resolver())
+            .find_map(|x| x.err())
+            .unwrap();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+
+        let mut tokens = vec![id("living"), Token::Equal, Token::Open];
+        for i in 0..2000 {
+            tokens.extend_from_slice(&[Token::U32(i), Token::Equal, Token::Open, Token::Close]);
+        }
+        tokens.push(Token::Close);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let err = file
+            .melter()
+            .options(MeltOptions::new().deadline(Instant::now()))
+            .melt(Vec::new(), &resolver())
+            .unwrap_err();
+        assert!(matches!(err.kind(), Ck3ErrorKind::BudgetExceeded));
+    }
+
+    #[test]
+    fn test_melt_inspiration_gold() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("inspirations_manager"),
+            Token::Equal,
+            Token::Open,
+            id("inspirations"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(7),
+            Token::Equal,
+            Token::Open,
+            id("owner"),
+            Token::Equal,
+            Token::U32(2),
+            id("gold"),
+            Token::Equal,
+            Token::F64(13_304_397_i64.to_le_bytes()),
+            Token::Close,
+            Token::Close,
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("gold=406.01797"), "{}", out);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_melt_key_filter() {
+        use crate::models::Gamestate;
+
+        let mut tokens = living();
+        tokens.truncate(tokens.len() - 1);
+        for (character, gold) in [(3u32, 13_304_397_i64), (4, 4_647_000)] {
+            tokens.extend_from_slice(&[
+                Token::U32(character),
+                Token::Equal,
+                Token::Open,
+                id("traits"),
+                Token::Equal,
+                Token::Open,
+                Token::U32(1),
+                Token::U32(2),
+                Token::U32(3),
+                Token::Close,
+                id("first_name"),
+                Token::Equal,
+                Token::Id(0x1fff),
+                id("alive_data"),
+                Token::Equal,
+                Token::Open,
+                id("gold"),
+                Token::Equal,
+                Token::F64(gold.to_le_bytes()),
+                Token::Close,
+                Token::Close,
+            ]);
+        }
+        tokens.push(Token::Close);
+
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let resolver = resolver();
+        let mut zip_sink = Vec::new();
+        let gamestate: Gamestate = file
+            .parse(&mut zip_sink)
+            .unwrap()
+            .deserializer(&resolver)
+            .deserialize()
+            .unwrap();
+        let with_gold = gamestate
+            .living
+            .values()
+            .filter(|x| x.alive_data.as_ref().and_then(|x| x.gold).is_some())
+            .count();
+
+        // Truncated arrays and commented tokens don't disturb the paths
+        let regex = regex::Regex::new("^gold$").unwrap();
+        let options = MeltOptions::new()
+            .key_filter(regex)
+            .max_array_len(1)
+            .comment_unknown_tokens(true);
+        let out = melt(&file, options.clone());
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), with_gold, "{}", out);
+        assert_eq!(lines[0], "living.3.alive_data.gold=406.01797");
+        assert!(lines[1].starts_with("living.4.alive_data.gold="));
+
+        let melted_lines = file
+            .melt_lines(&resolver, options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(melted_lines, lines);
+
+        let regex = regex::Regex::new("^(save_game_version|first_name)$").unwrap();
+        let options = MeltOptions::new().key_filter(regex);
+        let out = melt(&file, options.clone());
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "meta_data.save_game_version=3",
+                "living.1.first_name=\"Ivar\"",
+                "living.2.first_name=\"Halfdan\"",
+                "living.3.first_name=__unknown_0x1fff",
+                "living.4.first_name=__unknown_0x1fff",
+            ]
+        );
+
+        // Plaintext saves are filtered the same as binary saves
+        let text = melt(&file, MeltOptions::new());
+        let text_file = Ck3File::from_slice(text.as_bytes()).unwrap();
+        assert_eq!(melt(&text_file, options.clone()), out);
+        let text_lines = text_file
+            .melt_lines(&resolver, options)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(text_lines, lines);
+    }
+
+    #[test]
+    fn test_melt_quoting_policy() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("name_list"),
+            Token::Equal,
+            Token::Quoted(Scalar::new(b"name_list_norse")),
+            id("first_name"),
+            Token::Equal,
+            Token::Quoted(Scalar::new(b"Ragnar Lodbrok")),
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let options = MeltOptions::new().quoting_policy(QuotingPolicy::GameDefault);
+        let out = melt(&file, options);
+        assert!(out.contains("first_name=\"Ivar\""));
+        assert!(out.contains("name_list=name_list_norse"));
+        assert!(out.contains("first_name=\"Ragnar Lodbrok\""));
+
+        let options = MeltOptions::new().quoting_policy(QuotingPolicy::QuoteAll);
+        let out = melt(&file, options);
+        assert!(out.contains("first_name=\"Ivar\""));
+        assert!(out.contains("name_list=\"name_list_norse\""));
+        assert!(out.contains("first_name=\"Ragnar Lodbrok\""));
+
+        let options = MeltOptions::new().quoting_policy(QuotingPolicy::MinimalQuoting);
+        let out = melt(&file, options);
+        assert!(out.contains("first_name=Ivar"));
+        assert!(out.contains("name_list=name_list_norse"));
+        assert!(out.contains("first_name=\"Ragnar Lodbrok\""));
+    }
+
+    #[test]
+    fn test_melt_id_map() {
+        let mut tokens = living();
+        tokens.truncate(tokens.len() - 2);
+        tokens.extend_from_slice(&[
+            id("liege"),
+            Token::Equal,
+            Token::U32(1),
+            id("dynasty_house"),
+            Token::Equal,
+            Token::U32(1),
+            Token::Close,
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let id_map = [(1, 1001), (2, 1002)].into_iter().collect();
+        let out = melt(&file, MeltOptions::new().with_id_map(id_map));
+        assert!(out.contains("1001={"), "{}", out);
+        assert!(out.contains("1002={"), "{}", out);
+        assert!(out.contains("liege=1001"), "{}", out);
+        assert!(out.contains("dynasty_house=1\n"), "{}", out);
+        assert!(!out.contains("\n\t1={"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_id_map_non_id_value() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("liege"),
+            Token::Equal,
+            Token::I32(-1),
+            id("traits"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(1),
+            Token::Equal,
+            Token::U32(2),
+            Token::Close,
+            id("owner"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(1),
+            Token::U32(2),
+            Token::Close,
+            id("prestige"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(2),
+            Token::Equal,
+            Token::U32(1),
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let id_map = [(1, 1001), (2, 1002)].into_iter().collect();
+        let out = melt(&file, MeltOptions::new().with_id_map(id_map));
+        assert!(out.contains("liege=-1\n"), "{}", out);
+        assert!(out.contains("\t1=2\n"), "{}", out);
+        assert!(out.contains("\t2=1\n"), "{}", out);
+        assert!(out.contains("\t1 2\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_deserialize_stable() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_melt_deserialize_stable(&file, &resolver());
+    }
+
+    #[test]
+    fn test_melt_deserialize_stable_gold() {
+        let mut tokens = alive_data(&[
+            id("gold"),
+            Token::Equal,
+            Token::F64(13_304_397_i64.to_le_bytes()),
+        ]);
+        tokens.extend_from_slice(&[
+            id("inspirations_manager"),
+            Token::Equal,
+            Token::Open,
+            id("inspirations"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(7),
+            Token::Equal,
+            Token::Open,
+            id("gold"),
+            Token::Equal,
+            Token::F64(4_647_000_i64.to_le_bytes()),
+            Token::Close,
+            Token::Close,
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        assert_melt_deserialize_stable(&file, &resolver());
+
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let resolver = resolver();
+        let game: crate::models::Gamestate = parsed.deserializer(&resolver).deserialize().unwrap();
+        let gold = game.living[&2].alive_data.as_ref().and_then(|x| x.gold);
+        assert_eq!(gold, Some(406.01797));
+    }
+
+    /// Decodes f64 values as hundredths
+    pub(crate) struct CentFlavor(pub(crate) Utf8Encoding);
+
+    impl jomini::Encoding for CentFlavor {
+        fn decode<'a>(&self, data: &'a [u8]) -> std::borrow::Cow<'a, str> {
+            self.0.decode(data)
+        }
+    }
+
+    impl BinaryFlavor for CentFlavor {
+        fn visit_f32(&self, data: [u8; 4]) -> f32 {
+            f32::from_le_bytes(data)
+        }
+
+        fn visit_f64(&self, data: [u8; 8]) -> f64 {
+            i64::from_le_bytes(data) as f64 / 100.0
+        }
+    }
+
+    impl Ck3BinaryFlavor for CentFlavor {
+        fn float_reencoding(&self) -> bool {
+            false
+        }
+
+        fn unquote_token(&self, _token: &str) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_melt_with_flavor() {
+        let data = binary_save(&[
+            id("gold"),
+            Token::Equal,
+            Token::F64(12_345_i64.to_le_bytes()),
+        ]);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("gold=12.345"), "{}", out);
+
+        let options = MeltOptions::new().with_flavor(Box::new(CentFlavor(Utf8Encoding::new())));
+        let out = melt(&file, options);
+        assert!(out.contains("gold=123.45"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_i64_date() {
+        let birth = i64::from(crate::Ck3Date::from_ymd(867, 1, 1).to_binary());
+        let data = binary_save(&[
+            id("living"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(1),
+            Token::Equal,
+            Token::Open,
+            id("birth"),
+            Token::Equal,
+            Token::I64(birth),
+            Token::Close,
+            Token::Close,
+        ]);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("birth=867.1.1"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_include_characters() {
+        let mut tokens = living();
+        tokens.truncate(tokens.len() - 1);
+        tokens.extend_from_slice(&[
+            Token::U32(3),
+            Token::Equal,
+            Token::Open,
+            id("first_name"),
+            Token::Equal,
+            Token::Quoted(Scalar::new(b"Ubba")),
+            Token::Close,
+            Token::Close,
+            id("liege"),
+            Token::Equal,
+            Token::U32(2),
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let ids = [1, 3].into_iter().collect();
+        let out = melt(&file, MeltOptions::new().include_characters(ids));
+        assert!(out.contains("first_name=\"Ivar\""), "{}", out);
+        assert!(out.contains("first_name=\"Ubba\""), "{}", out);
+        assert!(!out.contains("Halfdan"), "{}", out);
+        assert!(out.contains("liege=2"), "{}", out);
+
+        let ids = [2].into_iter().collect();
+        let out = melt(&file, MeltOptions::new().include_characters(ids));
+        assert!(out.contains("first_name=\"Halfdan\""), "{}", out);
+        assert!(!out.contains("Ivar"), "{}", out);
+        assert!(!out.contains("Ubba"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_max_array_len() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[id("traits"), Token::Equal, Token::Open]);
+        tokens.extend((1..=5).map(Token::U32));
+        tokens.extend_from_slice(&[Token::Close, id("liege"), Token::Equal, Token::U32(2)]);
+        tokens.extend_from_slice(&[id("traits"), Token::Equal, Token::Open]);
+        for i in 0..3 {
+            tokens.extend_from_slice(&[Token::Open, Token::U32(i), Token::U32(i), Token::Close]);
+        }
+        tokens.push(Token::Close);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("\t1 2 3 4 5\n"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().max_array_len(3));
+        assert!(out.contains("\t1 2 3 # truncated\n}"), "{}", out);
+        assert!(out.contains("first_name=\"Ivar\""), "{}", out);
+        assert!(out.contains("first_name=\"Halfdan\""), "{}", out);
+        assert!(out.contains("liege=2"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().max_array_len(2));
+        assert!(out.contains("0 0"), "{}", out);
+        assert!(out.contains("1 1"), "{}", out);
+        assert!(!out.contains("2 2"), "{}", out);
+        assert!(out.ends_with("# truncated\n}\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_round_floats() {
+        let mut tokens = alive_data(&[
+            id("gold"),
+            Token::Equal,
+            Token::F64(13_304_397_i64.to_le_bytes()),
+        ]);
+        tokens.extend_from_slice(&[
+            id("prestige"),
+            Token::Equal,
+            Token::F64(13_304_397_i64.to_le_bytes()),
+            id("gold"),
+            Token::Equal,
+            Token::F32(1234.567_f32.to_le_bytes()),
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let out = melt(&file, MeltOptions::new().round_floats(Some(2)));
+        assert!(out.contains("gold=406.02"), "{}", out);
+        assert!(out.contains("prestige=13304.40"), "{}", out);
+        assert!(out.contains("gold=1234.57"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().round_floats(None));
+        assert!(out.contains("gold=406.01797"), "{}", out);
+        assert!(out.contains("gold=1234.5670"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_detect_dates() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[id("liege"), Token::Equal, Token::I32(56379360)]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("liege=1436.1.1"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().detect_dates(false));
+        assert!(out.contains("liege=56379360"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_retain_ironman() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("ironman"),
+            Token::Equal,
+            Token::Open,
+            id("save_game_version"),
+            Token::Equal,
+            Token::I32(3),
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(!out.contains("ironman"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().retain_ironman(true));
+        assert!(
+            out.contains("ironman={\n\tsave_game_version=3\n}"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_melt_comment_unknown_tokens() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("owner"),
+            Token::Equal,
+            Token::Id(0x3000),
+            id("liege"),
+            Token::Equal,
+            Token::U32(3),
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("owner=__unknown_0x3000"), "{}", out);
+
+        let out = melt(&file, MeltOptions::new().comment_unknown_tokens(true));
+        assert!(
+            out.contains("owner=\"\" # unknown token 0x3000\nliege=3"),
+            "{}",
+            out
+        );
+
+        let tape = jomini::TextTape::from_slice(&out.as_bytes()[24..]).unwrap();
+        let reader = tape.utf8_reader();
+        let (_, _, value) = reader
+            .fields()
+            .find(|(key, _, _)| key.read_str() == "owner")
+            .unwrap();
+        assert_eq!(value.read_str().unwrap(), "");
+        assert!(reader.fields().any(|(key, _, _)| key.read_str() == "liege"));
+    }
+
+    #[test]
+    fn test_melt_comment_unknown_tokens_in_array() {
+        let mut tokens = living();
+        tokens.extend_from_slice(&[
+            id("traits"),
+            Token::Equal,
+            Token::Open,
+            Token::Id(0x3000),
+            Token::Id(0x3001),
+            Token::Close,
+        ]);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new().comment_unknown_tokens(true));
+        let tape = jomini::TextTape::from_slice(&out.as_bytes()[24..]).unwrap();
+        let reader = tape.utf8_reader();
+        let (_, _, value) = reader
+            .fields()
+            .find(|(key, _, _)| key.read_str() == "traits")
+            .unwrap();
+        let values = value
+            .read_array()
+            .unwrap()
+            .values()
+            .map(|x| x.read_string().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["__unknown_0x3000", "__unknown_0x3001"]);
+    }
+
+    #[test]
+    fn test_melt_on_progress() {
+        let mut tokens = vec![id("traits"), Token::Equal, Token::Open];
+        tokens.extend((0..10_000).map(Token::I32));
+        tokens.push(Token::Close);
+        let data = binary_save(&tokens);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let progress = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = progress.clone();
+        let options = MeltOptions::new().on_progress(move |x| sink.lock().unwrap().push(x));
+        melt(&file, options);
+
+        let progress = progress.lock().unwrap();
+        assert!(progress.len() > 2, "{:?}", progress);
+        assert!(progress.windows(2).all(|x| x[0] <= x[1]), "{:?}", progress);
+        assert_eq!(progress.last().copied(), Some(data.len() as u64 - 24));
+    }
+
+    #[test]
+    fn test_melt_mixed_container_policy() {
+        let data = binary_save(&[
+            id("traits"),
+            Token::Equal,
+            Token::Open,
+            Token::I32(10),
+            Token::I32(0),
+            Token::Equal,
+            Token::I32(2),
+            Token::I32(1),
+            Token::Equal,
+            Token::I32(3),
+            Token::Close,
+        ]);
+        let file = Ck3File::from_slice(&data).unwrap();
+
+        let out = melt(&file, MeltOptions::new());
+        assert!(out.contains("traits={\n\t10 0=2 1=3\n}"), "{}", out);
+
+        let options = MeltOptions::new().mixed_container_policy(MixedPolicy::ForceObject);
+        let out = melt(&file, options);
+        assert!(out.contains("traits={\n\t10 0=2\n\t1=3\n}"), "{}", out);
+    }
+
+    #[test]
+    fn test_melt_unknown_token_offset() {
+        let offset = binary_save(&[]).len() - 24;
+        let data = binary_save(&[Token::Id(0x3000), Token::Equal, Token::I32(1)]);
+        let file = Ck3File::from_slice(&data).unwrap();
+        let options = MeltOptions::new().on_failed_resolve(FailedResolveStrategy::Error);
+        let err = file
+            .melter()
+            .options(options)
+            .melt(&mut Vec::new(), &resolver())
+            .unwrap_err();
+        match err.kind() {
+            Ck3ErrorKind::UnknownToken {
+                token_id,
+                offset: actual,
+            } => {
+                assert_eq!(*token_id, 0x3000);
+                assert_eq!(*actual, Some(offset));
+            }
+            x => panic!("unexpected error: {:?}", x),
+        }
+        assert!(err.to_string().ends_with(&format!("at offset: {}", offset)));
+    }
+
+    #[test]
+    fn test_melt_save_id_override() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let line = String::from("SAV01000000000000000000");
+        let options = MeltOptions::new().save_id_override(Some(line.clone()));
+        let out = melt(&file, options.clone());
+        assert_eq!(out.lines().next(), Some(line.as_str()));
+        assert!(out.contains("first_name=\"Ivar\""), "{}", out);
+
+        let resolver = resolver();
+        let melted_lines = file
+            .melt_lines(&resolver, options.clone())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(melted_lines[0], line);
+
+        let text = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.0.2\" }\n";
+        let file = Ck3File::from_slice(&text[..]).unwrap();
+        let out = melt(&file, options);
+        assert_eq!(out.lines().next(), Some(line.as_str()));
+        assert_eq!(&out.as_bytes()[24..], &text[24..]);
+    }
+
+    #[test]
+    fn test_melt_compressed_gzip() {
+        let data = binary_save(&living());
+        let file = Ck3File::from_slice(&data).unwrap();
+        let expected = melt(&file, MeltOptions::new());
+
+        let mut out = Vec::new();
+        melt_compressed(
+            &file,
+            &resolver(),
+            MeltOptions::new(),
+            &mut out,
+            CompressionKind::Gzip,
+        )
+        .unwrap();
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(out.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&decoded).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_resolve_token() {
+        let resolver: HashMap<u16, &str> =
+            [(0x10, "color1"), (0x11, "birth")].into_iter().collect();
+        let token = resolve_token(&resolver, 0x10).unwrap();
+        assert_eq!(token.name, "color1");
+        assert!(token.unquoted);
+        assert!(!token.date);
+
+        let token = resolve_token(&resolver, 0x11).unwrap();
+        assert!(!token.unquoted);
+        assert!(token.date);
+
+        assert!(resolve_token(&resolver, 0x12).is_none());
     }
-    Ok(())
 }
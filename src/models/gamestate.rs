@@ -1,31 +1,2365 @@
 use super::MetadataOwned;
-use crate::flavor::reencode_float;
+use crate::{flavor::reencode_float, Ck3Date, Ck3DateExt};
+use jomini::JominiDeserialize;
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, PartialEq, JominiDeserialize)]
 pub struct Gamestate {
     pub meta_data: MetadataOwned,
     pub living: HashMap<u64, LivingCharacter>,
+    #[jomini(default)]
+    pub pending_character_interactions: Option<Vec<PendingInteraction>>,
+    #[jomini(default)]
+    pub county_manager: Option<CountyManager>,
+    #[jomini(default)]
+    pub wars: Option<Wars>,
+    /// Holy orders keyed by id. Disbanded orders (`id=none`) are skipped.
+    #[jomini(default, deserialize_with = "deserialize_database")]
+    pub holy_orders: HashMap<u64, HolyOrder>,
+    #[jomini(default)]
+    pub struggles: Option<Vec<Struggle>>,
+    #[jomini(default)]
+    pub inspirations_manager: Option<InspirationsManager>,
+    #[jomini(default)]
+    pub culture_manager: Option<CultureManager>,
+    #[jomini(default)]
+    pub casus_belli_manager: Option<CasusBelliManager>,
+    #[jomini(default)]
+    pub council_task_manager: Option<CouncilTaskManager>,
+    #[jomini(default)]
+    pub landed_titles: Option<LandedTitles>,
+    #[jomini(default)]
+    pub vassal_contracts: Option<VassalContracts>,
+    /// Court positions keyed by the ruler whose court they belong to
+    #[jomini(default)]
+    pub court_positions: Option<HashMap<u64, CourtPositions>>,
+    #[jomini(default)]
+    pub dynasties: Option<Dynasties>,
+    #[jomini(default)]
+    pub religion: Option<Faiths>,
+    /// Knightly accolades (1.5+)
+    #[jomini(default)]
+    pub accolades: Option<Accolades>,
+    /// Legends being spread (1.13+)
+    #[jomini(default)]
+    pub legends: Option<Legends>,
+    /// Epidemics that are spreading (1.12+)
+    #[jomini(default)]
+    pub epidemics: Option<Epidemics>,
+    /// Story cycles that are in progress
+    #[jomini(default)]
+    pub stories: Option<Stories>,
+    /// Great projects, such as wonders, that are planned or being built (1.13+)
+    #[jomini(default)]
+    pub great_projects: Option<GreatProjects>,
+    #[jomini(default)]
+    pub character_memory_manager: Option<CharacterMemoryManager>,
+    /// Provinces keyed by their id
+    #[jomini(default)]
+    pub provinces: Option<HashMap<u64, Province>>,
+    /// The characters controlled by players. Multiplayer saves contain an
+    /// entry for each player.
+    #[jomini(duplicated, alias = "played_character")]
+    pub played_characters: Vec<PlayedCharacter>,
+    /// Trait names (eg: `brave`) indexed by the trait ids stored on characters
+    #[jomini(default)]
+    pub traits_lookup: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Gamestate {
+    /// Resolves a character's trait id (see [`LivingCharacter::traits`]) to the
+    /// trait's name
+    pub fn trait_name(&self, id: u16) -> Option<&str> {
+        self.traits_lookup.get(usize::from(id)).map(|x| x.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct PlayedCharacter {
+    /// The name of the player
+    pub name: Option<String>,
+    /// The character the player controls
+    pub character: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
 pub struct LivingCharacter {
+    pub first_name: Option<String>,
+    /// The localization key of an earned nickname (eg: `nick_the_boneless`)
+    pub nickname: Option<String>,
+    /// The base64 encoded genes used to render the character's portrait
+    pub dna: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub birth: Option<Ck3Date>,
+    pub dynasty_house: Option<u64>,
+    pub culture: Option<u64>,
+    pub faith: Option<u64>,
+    /// The character's traits as raw ids, which are indices into
+    /// [`Gamestate::traits_lookup`] rather than resolved tokens
+    #[serde(default)]
+    pub traits: Option<Vec<u16>>,
+    /// The faith the character is in the process of converting to
+    #[serde(default)]
+    pub faith_conversion: Option<FaithConversion>,
     pub alive_data: Option<AliveData>,
+    #[serde(default, rename = "skill")]
+    pub skills: Option<Skills>,
+    #[serde(default)]
+    pub travel_plan: Option<TravelPlan>,
+    #[serde(default)]
+    pub diarchy: Option<Diarchy>,
+    #[serde(default)]
+    pub men_at_arms: Option<Vec<Regiment>>,
+    /// The character's domicile. Only the inline object form is understood,
+    /// any other shape (eg: a reference to a domicile id) is read as `None`
+    #[serde(default, deserialize_with = "deserialize_domicile")]
+    pub domicile: Option<Domicile>,
+    /// The character's liege, absent for independent rulers
+    #[serde(default, deserialize_with = "deserialize_character_id")]
+    pub liege: Option<u64>,
+    /// The ruler whose court the character belongs to
+    #[serde(default, deserialize_with = "deserialize_character_id")]
+    pub employer: Option<u64>,
+    /// The strategies the AI pursues for the character, in the order they
+    /// appear
+    #[serde(default, deserialize_with = "deserialize_ai_strategies")]
+    pub ai_strategies: Option<Vec<AiStrategy>>,
+}
+
+impl LivingCharacter {
+    /// The lifestyle perks the character has unlocked
+    pub fn perks(&self) -> &[String] {
+        self.alive_data
+            .as_ref()
+            .map(|x| x.perks.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// A strategy the AI pursues for a character. The save keys each strategy
+/// by its type (eg: `ai_war_strategy=200`), with the value being the
+/// strategy's target or weight.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AiStrategy {
+    /// The strategy type (eg: `ai_war_strategy`)
+    #[serde(rename = "type")]
+    pub strategy_type: String,
+    /// The strategy's target or weight, depending on the strategy type
+    pub target: i32,
+}
+
+/// A character's ongoing conversion to another faith
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct FaithConversion {
+    /// The faith being converted to
+    pub faith: Option<u64>,
+    /// How far along the conversion is (0 to 100)
+    pub progress: Option<f64>,
+}
+
+/// The seat of a landless or administrative character (1.13+).
+///
+/// No 1.13+ sample save is available to verify this against, so every field
+/// is optional and unrecognized fields are ignored.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Domicile {
+    /// The province the domicile is located in
+    pub location: Option<u64>,
+    /// The domicile type (eg: `estate`)
+    #[serde(rename = "type")]
+    pub domicile_type: Option<String>,
+    #[serde(default)]
+    pub buildings: Vec<DomicileBuilding>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct DomicileBuilding {
+    /// The building key (eg: `estate_main_building_01`)
+    pub key: Option<String>,
+}
+
+/// A men-at-arms regiment raised by a character
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Regiment {
+    /// The men-at-arms type (eg: `huscarl`)
+    #[serde(rename = "type")]
+    pub maa_type: Option<String>,
+    /// The current number of soldiers
+    pub size: Option<u32>,
+}
+
+/// The diarchy of a ruler's realm (1.9+)
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Diarchy {
+    /// The diarchy type (eg: `regency`)
+    #[serde(rename = "type")]
+    pub diarchy_type: Option<String>,
+    /// The character serving as diarch
+    pub diarch: Option<u64>,
+    /// The mandate the liege has set for the diarch
+    pub mandate: Option<String>,
+}
+
+/// A character's active travel (1.9+)
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct TravelPlan {
+    /// The destination province
+    pub destination: Option<u64>,
+    /// How far along the route the character has traveled
+    pub progress: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub departure_date: Option<Ck3Date>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub arrival_date: Option<Ck3Date>,
+}
+
+/// The base skill levels of a character, stored in the save as a six element
+/// array
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "[i16; 6]")]
+pub struct Skills {
+    pub diplomacy: i16,
+    pub martial: i16,
+    pub stewardship: i16,
+    pub intrigue: i16,
+    pub learning: i16,
+    pub prowess: i16,
 }
 
-#[derive(Debug, Deserialize)]
+impl From<[i16; 6]> for Skills {
+    fn from(x: [i16; 6]) -> Self {
+        let [diplomacy, martial, stewardship, intrigue, learning, prowess] = x;
+        Skills {
+            diplomacy,
+            martial,
+            stewardship,
+            intrigue,
+            learning,
+            prowess,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, JominiDeserialize)]
 pub struct AliveData {
-    #[serde(default, deserialize_with = "deserialize_eu4_float")]
+    /// Gold, which is either a bare number or (1.16+) an object
+    #[jomini(default, deserialize_with = "deserialize_eu4_currency")]
     pub gold: Option<f64>,
+    /// Prestige, in the same representation as gold but never reencoded
+    #[jomini(default, deserialize_with = "deserialize_currency")]
+    pub prestige: Option<f64>,
+    /// Piety, in the same representation as gold but never reencoded
+    #[jomini(default, deserialize_with = "deserialize_currency")]
+    pub piety: Option<f64>,
     pub health: Option<f32>,
     pub income: Option<f32>,
+    pub tyranny: Option<f32>,
+    pub dread: Option<f32>,
+    /// The lifestyle perks the character has unlocked (eg: `family_man_perk`)
+    #[jomini(duplicated, alias = "perk")]
+    pub perks: Vec<String>,
+}
+
+/// An in-flight character interaction (eg: a marriage proposal) that is
+/// awaiting a response
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct PendingInteraction {
+    pub interaction: String,
+    pub actor: Option<u64>,
+    pub recipient: Option<u64>,
+    pub secondary_actor: Option<u64>,
+    pub secondary_recipient: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub date: Option<Ck3Date>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CountyManager {
+    /// Counties keyed by their title key (eg: `c_uppland`)
+    #[serde(default)]
+    pub counties: HashMap<String, County>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct County {
+    pub development: Option<f64>,
+    pub control: Option<f64>,
+    pub faith: Option<u64>,
+    pub culture: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Wars {
+    /// Active wars keyed by id. Wars that have ended (`id=none`) are skipped.
+    #[serde(default, deserialize_with = "deserialize_database")]
+    pub active_wars: HashMap<u64, War>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct War {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub start_date: Option<Ck3Date>,
+    pub attacker: Option<WarSide>,
+    pub defender: Option<WarSide>,
+    pub casus_belli: Option<CasusBelli>,
+}
+
+impl War {
+    /// The character leading the attackers (the first listed participant)
+    pub fn primary_attacker(&self) -> Option<u64> {
+        self.attacker.as_ref().and_then(WarSide::primary)
+    }
+
+    /// The character leading the defenders (the first listed participant)
+    pub fn primary_defender(&self) -> Option<u64> {
+        self.defender.as_ref().and_then(WarSide::primary)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct WarSide {
+    #[serde(default)]
+    pub participants: Vec<WarParticipant>,
+}
+
+impl WarSide {
+    fn primary(&self) -> Option<u64> {
+        self.participants.first().map(|x| x.character)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct HolyOrder {
+    pub name: Option<String>,
+    pub leader: Option<u64>,
+    /// Baronies the holy order has been granted
+    #[serde(default)]
+    pub leased_titles: Vec<u64>,
+}
+
+/// A regional struggle (1.7+)
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Struggle {
+    #[serde(rename = "type")]
+    pub struggle_type: Option<String>,
+    /// The current phase (eg: `struggle_iberia_phase_opportunity`)
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub involved_cultures: Vec<u64>,
+    #[serde(default)]
+    pub involved_faiths: Vec<u64>,
+    /// Catalysts that have contributed towards the next phase
+    #[serde(default)]
+    pub catalysts: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct InspirationsManager {
+    #[serde(default)]
+    pub inspirations: HashMap<u64, Inspiration>,
+}
+
+/// An inspired character working towards creating an artifact
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Inspiration {
+    #[serde(rename = "type")]
+    pub inspiration_type: Option<String>,
+    /// The inspired character
+    pub owner: Option<u64>,
+    /// The character funding the inspiration
+    pub sponsor: Option<u64>,
+    /// Gold invested into the inspiration so far
+    #[serde(default, deserialize_with = "deserialize_eu4_float")]
+    pub gold: Option<f64>,
+    pub progress: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CultureManager {
+    #[serde(default)]
+    pub cultures: HashMap<u64, Culture>,
+}
+
+impl CultureManager {
+    /// The distinct name pools referenced by cultures in the save
+    pub fn name_lists(&self) -> Vec<&str> {
+        let mut result = self
+            .cultures
+            .values()
+            .flat_map(|x| x.name_list.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+}
+
+#[derive(Debug, PartialEq, JominiDeserialize)]
+pub struct Culture {
+    /// The custom name of a hybrid or divergent culture
+    pub name: Option<String>,
+    /// The localization key of a culture defined by the game
+    pub localization_key: Option<String>,
+    /// The name pools that characters of this culture draw from
+    #[jomini(duplicated)]
+    pub name_list: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct WarParticipant {
+    pub character: u64,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CasusBelli {
+    #[serde(rename = "type")]
+    pub cb_type: Option<String>,
+    pub claimant: Option<u64>,
+    #[serde(default)]
+    pub target_titles: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CouncilTaskManager {
+    /// The tasks councillors are performing keyed by the task id
+    #[serde(default)]
+    pub council_tasks: HashMap<u64, CouncilTask>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CouncilTask {
+    /// The councillor performing the task
+    pub councillor: Option<u64>,
+    /// The task type (eg: `task_collect_taxes`)
+    #[serde(rename = "type")]
+    pub task_type: Option<String>,
+    pub progress: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CasusBelliManager {
+    /// Casus belli that realms currently hold against one another
+    #[serde(default)]
+    pub active_casus_belli: HashMap<u64, ActiveCasusBelli>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ActiveCasusBelli {
+    #[serde(rename = "type")]
+    pub cb_type: Option<String>,
+    pub attacker: Option<u64>,
+    pub defender: Option<u64>,
+    pub target_title: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Accolades {
+    /// Accolades keyed by their id
+    #[serde(default)]
+    pub accolades: HashMap<u64, Accolade>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Accolade {
+    pub name: Option<String>,
+    /// The character holding the accolade
+    pub knight: Option<u64>,
+    /// The liege who granted the accolade
+    pub owner: Option<u64>,
+    /// The accolade type (eg: `cavalry_leader_attribute`)
+    #[serde(rename = "type")]
+    pub accolade_type: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Province {
+    pub holding: Option<Holding>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Holding {
+    /// The holding type (eg: `castle_holding`)
+    #[serde(rename = "type")]
+    pub holding_type: Option<String>,
+    /// The holding's building slots, where an empty slot has no type
+    #[serde(default)]
+    pub buildings: Option<Vec<Building>>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Building {
+    /// The building (eg: `curtain_walls_01`)
+    #[serde(rename = "type")]
+    pub building_type: Option<String>,
+    /// How far along the building's construction is, absent once built
+    pub construction_progress: Option<f32>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CharacterMemoryManager {
+    /// Memories keyed by their id
+    #[serde(default)]
+    pub database: HashMap<u64, CharacterMemory>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CharacterMemory {
+    /// The memory type (eg: `murdered_relative`)
+    #[serde(rename = "type")]
+    pub memory_type: Option<String>,
+    /// The character who has the memory
+    pub owner: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub creation_date: Option<Ck3Date>,
+    /// The characters involved in the memory in the order the save lists them
+    #[serde(default, deserialize_with = "deserialize_memory_participants")]
+    pub participants: Vec<MemoryParticipant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryParticipant {
+    /// The part the character played (eg: `perpetrator` or `victim`)
+    pub role: String,
+    pub character: u64,
+}
+
+fn deserialize_memory_participants<'de, D>(
+    deserializer: D,
+) -> Result<Vec<MemoryParticipant>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ParticipantsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ParticipantsVisitor {
+        type Value = Vec<MemoryParticipant>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a role keyed map of memory participants")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut result = Vec::new();
+            while let Some((role, character)) = map.next_entry::<String, u64>()? {
+                result.push(MemoryParticipant { role, character });
+            }
+            Ok(result)
+        }
+    }
+
+    deserializer.deserialize_map(ParticipantsVisitor)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct GreatProjects {
+    /// Great projects keyed by their id
+    #[serde(default)]
+    pub great_projects: HashMap<u64, GreatProject>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct GreatProject {
+    /// The project type (eg: `gp_grand_cathedral`)
+    #[serde(rename = "type")]
+    pub project_type: Option<String>,
+    pub level: Option<u32>,
+    /// The province the project is built in
+    pub location: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Stories {
+    /// Story cycles keyed by their id
+    #[serde(default)]
+    pub active: HashMap<u64, StoryCycle>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct StoryCycle {
+    /// The story cycle type (eg: `story_cycle_pet_dog`)
+    #[serde(rename = "type")]
+    pub story_type: Option<String>,
+    /// The character the story is about
+    pub owner: Option<u64>,
+    /// Values stored on the story by script effects keyed by variable name
+    #[serde(default)]
+    pub variables: HashMap<String, VariableValue>,
+}
+
+/// A value stored in a script variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableValue {
+    Bool(bool),
+    Number(f64),
+    /// Any other scalar (eg: a flag like `flag:dog_name_1`)
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for VariableValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct VariableVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for VariableVisitor {
+            type Value = VariableValue;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a variable value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(VariableValue::Bool(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(VariableValue::Number(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(VariableValue::Number(v as f64))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(VariableValue::Number(v as f64))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let value = match v {
+                    "yes" => VariableValue::Bool(true),
+                    "no" => VariableValue::Bool(false),
+                    _ => v
+                        .parse()
+                        .map(VariableValue::Number)
+                        .unwrap_or_else(|_| VariableValue::Text(String::from(v))),
+                };
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_any(VariableVisitor)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Epidemics {
+    #[serde(default)]
+    pub active_epidemics: HashMap<u64, Epidemic>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Epidemic {
+    /// The disease (eg: `bubonic_plague`)
+    #[serde(rename = "type")]
+    pub epidemic_type: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub start_date: Option<Ck3Date>,
+    /// The provinces the epidemic has spread to
+    #[serde(default)]
+    pub provinces: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Legends {
+    /// Legends keyed by their id
+    #[serde(default)]
+    pub legends: HashMap<u64, Legend>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Legend {
+    /// The legend type (eg: `heroic`)
+    #[serde(rename = "type")]
+    pub legend_type: Option<String>,
+    /// The character the legend is about
+    pub protagonist: Option<u64>,
+    /// The character that commissioned the legend
+    pub owner: Option<u64>,
+    /// The text selected for each chapter of the legend keyed by the chapter
+    /// (eg: `opening`)
+    #[serde(default)]
+    pub chapters: HashMap<String, String>,
+}
+
+/// The `religion` section of the save
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Faiths {
+    /// Faiths keyed by their id
+    #[serde(default)]
+    pub faiths: HashMap<u64, Faith>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Faith {
+    /// The faith's key (eg: `catholic`)
+    pub tag: Option<String>,
+    /// The religion the faith belongs to
+    pub religion: Option<u64>,
+    pub fervor: Option<f64>,
+}
+
+/// The save nests dynasties and their houses under a top level `dynasties`
+/// object. Only the dynasties themselves are read.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Dynasties {
+    /// Dynasties keyed by their id, as found in `dynasties.dynasties`
+    #[serde(default)]
+    pub dynasties: HashMap<u64, Dynasty>,
+}
+
+#[derive(Debug, PartialEq, JominiDeserialize)]
+pub struct Dynasty {
+    pub name: Option<String>,
+    /// The prestige the dynasty has accumulated over its lifetime
+    pub prestige_total: Option<f64>,
+    /// The dynasty legacy perks that have been unlocked (eg: `warfare_legacy_1`)
+    #[jomini(duplicated, alias = "perk")]
+    pub perks: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct LandedTitles {
+    /// Titles keyed by their id. Destroyed titles, which the save records as
+    /// `none`, are omitted.
+    #[serde(default, deserialize_with = "deserialize_database")]
+    pub landed_titles: HashMap<u64, LandedTitle>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct LandedTitle {
+    /// The title key (eg: `k_norway`)
+    pub key: Option<String>,
+    pub holder: Option<u64>,
+    /// The succession laws in effect for the title (eg: `single_heir_succession_law`)
+    #[serde(default)]
+    pub succession_laws: Vec<String>,
+    /// Changes in the title's holder in the order they occurred
+    #[serde(default, deserialize_with = "deserialize_title_history")]
+    pub history: Option<Vec<TitleHistoryEvent>>,
+    /// The gold held in reserve by the realm, separate from the holder's
+    /// personal gold. Only present for realms that keep a treasury.
+    #[serde(default, deserialize_with = "deserialize_eu4_currency")]
+    pub treasury: Option<f64>,
+}
+
+/// A change in a title's holder
+///
+/// The save records history as a date keyed object where the value is either
+/// the new holder or an object describing the change.
+#[derive(Debug, PartialEq)]
+pub struct TitleHistoryEvent {
+    pub date: Ck3Date,
+    /// The new holder. `None` when the title was destroyed.
+    pub holder: Option<u64>,
+    /// How the title changed hands (eg: `conquest`)
+    pub event_type: Option<String>,
+}
+
+fn deserialize_title_history<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<TitleHistoryEvent>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct EventDate(Ck3Date);
+
+    impl<'de> Deserialize<'de> for EventDate {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(Ck3DateVisitor).map(EventDate)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct EventObject {
+        #[serde(rename = "type")]
+        event_type: Option<String>,
+        holder: Option<u64>,
+    }
+
+    struct Event(EventObject);
+
+    impl<'de> Deserialize<'de> for Event {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct EventVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for EventVisitor {
+                type Value = Event;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("a title holder or history event")
+                }
+
+                fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    self.visit_u64(u64::from(v))
+                }
+
+                fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Event(EventObject {
+                        event_type: None,
+                        holder: Some(v),
+                    }))
+                }
+
+                fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    self.visit_i64(i64::from(v))
+                }
+
+                fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Event(EventObject {
+                        event_type: None,
+                        holder: u64::try_from(v).ok(),
+                    }))
+                }
+
+                fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Event(EventObject {
+                        event_type: None,
+                        holder: v.parse().ok(),
+                    }))
+                }
+
+                fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>,
+                {
+                    let de = serde::de::value::MapAccessDeserializer::new(map);
+                    EventObject::deserialize(de).map(Event)
+                }
+            }
+
+            deserializer.deserialize_any(EventVisitor)
+        }
+    }
+
+    struct HistoryVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for HistoryVisitor {
+        type Value = Vec<TitleHistoryEvent>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a date keyed title history")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut events = Vec::new();
+            while let Some((EventDate(date), Event(event))) = map.next_entry()? {
+                events.push(TitleHistoryEvent {
+                    date,
+                    holder: event.holder,
+                    event_type: event.event_type,
+                });
+            }
+            Ok(events)
+        }
+    }
+
+    deserializer.deserialize_map(HistoryVisitor).map(Some)
+}
+
+/// Deserializes a database of objects keyed by id where removed entries are
+/// written as `id=none`. The removed entries are skipped.
+fn deserialize_database<'de, D, T>(deserializer: D) -> Result<HashMap<u64, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct MaybeEntry<T>(Option<T>);
+
+    impl<'de, T> Deserialize<'de> for MaybeEntry<T>
+    where
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct MaybeEntryVisitor<T>(std::marker::PhantomData<T>);
+
+            impl<'de, T> serde::de::Visitor<'de> for MaybeEntryVisitor<T>
+            where
+                T: Deserialize<'de>,
+            {
+                type Value = MaybeEntry<T>;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("an object or none")
+                }
+
+                fn visit_str<E>(self, _v: &str) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(MaybeEntry(None))
+                }
+
+                fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>,
+                {
+                    let de = serde::de::value::MapAccessDeserializer::new(map);
+                    T::deserialize(de).map(|x| MaybeEntry(Some(x)))
+                }
+            }
+
+            deserializer.deserialize_any(MaybeEntryVisitor(std::marker::PhantomData))
+        }
+    }
+
+    let entries: HashMap<u64, MaybeEntry<T>> = HashMap::deserialize(deserializer)?;
+    let result = entries
+        .into_iter()
+        .filter_map(|(id, entry)| entry.0.map(|x| (id, x)))
+        .collect();
+    Ok(result)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct VassalContracts {
+    /// Contracts in effect keyed by id. Contracts that have been dissolved
+    /// (`id=none`) are skipped.
+    #[serde(default, deserialize_with = "deserialize_database")]
+    pub active: HashMap<u64, VassalContract>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct VassalContract {
+    pub liege: Option<u64>,
+    /// The level of each obligation keyed by its contract term (eg:
+    /// `feudal_government_taxes`)
+    #[serde(default)]
+    pub obligations: HashMap<String, u8>,
+    #[serde(default, deserialize_with = "deserialize_eu4_float")]
+    pub vassal_power_value: Option<f64>,
+}
+
+impl VassalContract {
+    /// The level of the given obligation, if the contract specifies it
+    pub fn obligation(&self, term: &str) -> Option<u8> {
+        self.obligations.get(term).copied()
+    }
 }
 
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CourtPositions {
+    /// The character filling each position keyed by position type (eg:
+    /// `court_physician_court_position`)
+    #[serde(default)]
+    pub positions: HashMap<String, u64>,
+}
+
+/// Deserializes a float that binary saves store in the alternate (Q49.15)
+/// encoding. Only binary floats are reencoded, as plaintext values (including
+/// those of a melted save) have already been decoded.
 pub(crate) fn deserialize_eu4_float<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let val: Option<f64> = Option::deserialize(deserializer)?;
-    val.map(reencode_float).map(Ok).transpose()
+    struct Eu4Float(f64);
+
+    impl<'de> Deserialize<'de> for Eu4Float {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct Eu4FloatVisitor;
+
+            impl serde::de::Visitor<'_> for Eu4FloatVisitor {
+                type Value = Eu4Float;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("a number")
+                }
+
+                fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Eu4Float(f64::from(v)))
+                }
+
+                fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Eu4Float(reencode_float(v)))
+                }
+
+                fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Eu4Float(v as f64))
+                }
+
+                fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(Eu4Float(v as f64))
+                }
+
+                fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    v.parse()
+                        .map(Eu4Float)
+                        .map_err(|_| E::custom(format!("invalid number: {}", v)))
+                }
+            }
+
+            deserializer.deserialize_any(Eu4FloatVisitor)
+        }
+    }
+
+    let val: Option<Eu4Float> = Option::deserialize(deserializer)?;
+    Ok(val.map(|x| x.0))
+}
+
+/// A currency (eg: gold, prestige, piety, or renown) that the save stores as
+/// either a bare number (`gold=133.04397`) or (1.16+) an object with the
+/// current amount under `value` or `currency` and optionally the lifetime
+/// total under `accumulated` (`prestige={ currency=10.5 accumulated=100 }`).
+/// An object without the current amount is an error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrencyValue {
+    value: f64,
+    accumulated: Option<f64>,
+}
+
+/// Character gold, see [`CurrencyValue`]
+pub type GoldData = CurrencyValue;
+
+impl CurrencyValue {
+    fn new(value: f64) -> Self {
+        CurrencyValue {
+            value,
+            accumulated: None,
+        }
+    }
+
+    /// The currency's value as found in the save
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The total amount of the currency ever accumulated, if the save
+    /// records it
+    pub fn accumulated(&self) -> Option<f64> {
+        self.accumulated
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(CurrencyVisitor { reencode: false })
+    }
+}
+
+/// Visits a [`CurrencyValue`], optionally reencoding binary floats
+struct CurrencyVisitor {
+    reencode: bool,
+}
+
+impl<'de> serde::de::Visitor<'de> for CurrencyVisitor {
+    type Value = CurrencyValue;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a number or an object with a value")
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(CurrencyValue::new(f64::from(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.reencode {
+            Ok(CurrencyValue::new(reencode_float(v)))
+        } else {
+            Ok(CurrencyValue::new(v))
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(CurrencyValue::new(v as f64))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(CurrencyValue::new(v as f64))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse()
+            .map(CurrencyValue::new)
+            .map_err(|_| E::custom(format!("invalid number: {}", v)))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Currency {
+            #[serde(alias = "currency")]
+            value: f64,
+            accumulated: Option<f64>,
+        }
+
+        let de = serde::de::value::MapAccessDeserializer::new(map);
+        Currency::deserialize(de).map(|x| CurrencyValue {
+            value: x.value,
+            accumulated: x.accumulated,
+        })
+    }
+}
+
+/// Deserializes a [`CurrencyValue`] as it is found in the save
+pub(crate) fn deserialize_currency<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let val: Option<CurrencyValue> = Option::deserialize(deserializer)?;
+    Ok(val.map(|x| x.value()))
+}
+
+/// Deserializes a [`CurrencyValue`] with the same reencoding as
+/// [`deserialize_eu4_float`]
+pub(crate) fn deserialize_eu4_currency<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Eu4Currency(f64);
+
+    impl<'de> Deserialize<'de> for Eu4Currency {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let visitor = CurrencyVisitor { reencode: true };
+            let val = deserializer.deserialize_any(visitor)?;
+            Ok(Eu4Currency(val.value()))
+        }
+    }
+
+    let val: Option<Eu4Currency> = Option::deserialize(deserializer)?;
+    Ok(val.map(|x| x.0))
+}
+
+/// Deserializes a date from its textual or binary form, where the binary form
+/// may be encoded as either a 32 or 64 bit integer
+pub(crate) fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<Ck3Date>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(Ck3DateVisitor).map(Some)
+}
+
+struct Ck3DateVisitor;
+
+impl serde::de::Visitor<'_> for Ck3DateVisitor {
+    type Value = Ck3Date;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a date")
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ck3Date::from_binary(v).ok_or_else(|| E::custom(format!("invalid binary date: {}", v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ck3Date::from_binary_i64(v).ok_or_else(|| E::custom(format!("invalid binary date: {}", v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ck3Date::parse(v).map_err(|_| E::custom(format!("invalid date: {}", v)))
+    }
+}
+
+/// Deserializes a character id where the save may write `none` in place of
+/// an id
+fn deserialize_character_id<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct CharacterIdVisitor;
+
+    impl serde::de::Visitor<'_> for CharacterIdVisitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a character id or none")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(u64::try_from(v).ok())
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(v))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            match v {
+                "none" => Ok(None),
+                _ => v
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| E::custom(format!("invalid character id: {}", v))),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(CharacterIdVisitor)
+}
+
+fn deserialize_ai_strategies<'de, D>(deserializer: D) -> Result<Option<Vec<AiStrategy>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct AiStrategiesVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for AiStrategiesVisitor {
+        type Value = Option<Vec<AiStrategy>>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("ai strategies")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut result = Vec::new();
+            while let Some(x) = seq.next_element::<AiStrategy>()? {
+                result.push(x);
+            }
+            Ok(Some(result))
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut result = Vec::new();
+            while let Some((strategy_type, target)) = map.next_entry::<String, i32>()? {
+                result.push(AiStrategy {
+                    strategy_type,
+                    target,
+                });
+            }
+            Ok(Some(result))
+        }
+    }
+
+    deserializer.deserialize_any(AiStrategiesVisitor)
+}
+
+fn deserialize_domicile<'de, D>(deserializer: D) -> Result<Option<Domicile>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct DomicileVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for DomicileVisitor {
+        type Value = Option<Domicile>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a domicile")
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let de = serde::de::value::MapAccessDeserializer::new(map);
+            Domicile::deserialize(de).map(Some)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+            Ok(None)
+        }
+
+        fn visit_i64<E>(self, _v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_u64<E>(self, _v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_str<E>(self, _v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+    }
+
+    deserializer.deserialize_any(DomicileVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jomini::text::de::from_utf8_slice;
+
+    #[test]
+    fn test_liege_and_employer() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={ liege=none employer=16322 }
+                200={ liege=16322 employer=16322 }
+                201={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        assert_eq!(game.living[&16322].liege, None);
+        assert_eq!(game.living[&16322].employer, Some(16322));
+        assert_eq!(game.living[&200].liege, Some(16322));
+        assert_eq!(game.living[&200].employer, Some(16322));
+        assert_eq!(game.living[&201].employer, None);
+    }
+
+    #[test]
+    fn test_meta_date() {
+        let data = br#"
+            meta_data={ version="1.9.0" meta_date=1066.9.15 meta_real_date=120.9.2 }
+            living={}
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let meta = &game.meta_data;
+        assert_eq!(meta.meta_date, Some(Ck3Date::from_ymd(1066, 9, 15)));
+        assert_eq!(meta.meta_real_date, Some(Ck3Date::from_ymd(120, 9, 2)));
+
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+        "#;
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        assert_eq!(game.meta_data.meta_date, None);
+    }
+
+    #[test]
+    fn test_played_characters() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            played_character={ name="Ivar" character=16322 player=0 }
+            played_character={ name="Halfdan" character=200 player=1 }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let players = &game.played_characters;
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].name.as_deref(), Some("Ivar"));
+        assert_eq!(players[0].character, Some(16322));
+        assert_eq!(players[1].character, Some(200));
+
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+        "#;
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        assert!(game.played_characters.is_empty());
+    }
+
+    #[test]
+    fn test_wars() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            wars={
+                active_wars={
+                    12={
+                        name="Ivar's Conquest of Mercia"
+                        start_date=867.3.2
+                        attacker={ participants={ { character=16322 } { character=200 } } }
+                        defender={ participants={ { character=9999 } } }
+                        casus_belli={ type="county_conquest_cb" target_titles={ 1234 1235 } }
+                    }
+                    13=none
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let wars = game.wars.unwrap();
+        assert_eq!(wars.active_wars.len(), 1);
+        let war = &wars.active_wars[&12];
+        assert_eq!(war.start_date, Some(Ck3Date::from_ymd(867, 3, 2)));
+        assert_eq!(war.primary_attacker(), Some(16322));
+        assert_eq!(war.primary_defender(), Some(9999));
+        let cb = war.casus_belli.as_ref().unwrap();
+        assert_eq!(cb.cb_type.as_deref(), Some("county_conquest_cb"));
+        assert_eq!(cb.target_titles, vec![1234, 1235]);
+    }
+
+    #[test]
+    fn test_character_skills() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={ skill={ 8 14 6 5 3 11 } }
+                16323={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let skills = game.living[&16322].skills.unwrap();
+        assert_eq!(skills.martial, 14);
+        assert_eq!(skills.prowess, 11);
+        assert_eq!(game.living[&16323].skills, None);
+    }
+
+    #[test]
+    fn test_travel_plan() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    travel_plan={
+                        destination=2987
+                        progress=0.35
+                        departure_date=1066.9.15
+                    }
+                }
+                16323={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let plan = game.living[&16322].travel_plan.as_ref().unwrap();
+        assert_eq!(plan.destination, Some(2987));
+        assert_eq!(plan.progress, Some(0.35));
+        assert_eq!(plan.departure_date, Some(Ck3Date::from_ymd(1066, 9, 15)));
+        assert_eq!(plan.arrival_date, None);
+        assert!(game.living[&16323].travel_plan.is_none());
+    }
+
+    #[test]
+    fn test_struggles() {
+        let data = br#"
+            meta_data={ version="1.7.0" }
+            living={}
+            struggles={
+                {
+                    type=iberian_struggle
+                    phase=struggle_iberia_phase_opportunity
+                    involved_cultures={ 12 13 }
+                    involved_faiths={ 4 5 6 }
+                    catalysts={ catalyst_war_between_involved catalyst_marriage }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let struggles = game.struggles.unwrap();
+        assert_eq!(
+            struggles[0].phase.as_deref(),
+            Some("struggle_iberia_phase_opportunity")
+        );
+        assert_eq!(struggles[0].involved_faiths, vec![4, 5, 6]);
+        assert_eq!(struggles[0].catalysts.len(), 2);
+    }
+
+    #[test]
+    fn test_inspirations() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            inspirations_manager={
+                inspirations={
+                    7={
+                        type=weapon_inspiration
+                        owner=33922
+                        sponsor=16322
+                        gold=32.768
+                        progress=12.25
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let inspirations = game.inspirations_manager.unwrap().inspirations;
+        let inspiration = &inspirations[&7];
+        assert_eq!(
+            inspiration.inspiration_type.as_deref(),
+            Some("weapon_inspiration")
+        );
+        assert_eq!(inspiration.owner, Some(33922));
+        assert_eq!(inspiration.sponsor, Some(16322));
+        assert_eq!(inspiration.gold, Some(32.768));
+    }
+
+    #[test]
+    fn test_culture_name_lists() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            culture_manager={
+                cultures={
+                    12={
+                        localization_key=norse
+                        name_list=name_list_norse
+                    }
+                    40={
+                        name="Anglo-Norse"
+                        name_list=name_list_norse
+                        name_list=name_list_anglo_saxon
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let manager = game.culture_manager.unwrap();
+        let norse = &manager.cultures[&12];
+        assert_eq!(norse.localization_key.as_deref(), Some("norse"));
+        assert_eq!(norse.name_list, vec![String::from("name_list_norse")]);
+        assert_eq!(manager.cultures[&40].name.as_deref(), Some("Anglo-Norse"));
+        assert_eq!(
+            manager.name_lists(),
+            vec!["name_list_anglo_saxon", "name_list_norse"]
+        );
+    }
+
+    #[test]
+    fn test_currencies() {
+        let data = br#"
+            meta_data={ version="1.15.0" }
+            living={
+                16322={ alive_data={ gold=32.768 prestige=65.536 piety=98.304 } }
+                16323={ alive_data={ health=5.2 } }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ruler = game.living[&16322].alive_data.as_ref().unwrap();
+        assert_eq!(ruler.gold, Some(32.768));
+        assert_eq!(ruler.prestige, Some(65.536));
+        assert_eq!(ruler.piety, Some(98.304));
+        let other = game.living[&16323].alive_data.as_ref().unwrap();
+        assert_eq!(other.prestige, None);
+
+        let data = br#"
+            meta_data={ version="1.16.0" }
+            living={
+                16322={
+                    alive_data={
+                        gold={ value=32.768 }
+                        prestige={ value=65.536 accumulated=131.072 }
+                        piety={ value=98.304 }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ruler = game.living[&16322].alive_data.as_ref().unwrap();
+        assert_eq!(ruler.gold, Some(32.768));
+        assert_eq!(ruler.prestige, Some(65.536));
+        assert_eq!(ruler.piety, Some(98.304));
+    }
+
+    #[test]
+    fn test_currency_value_shapes() {
+        use serde::de::{value::Error, IntoDeserializer};
+
+        fn value<'de, T: IntoDeserializer<'de, Error>>(x: T) -> f64 {
+            CurrencyValue::deserialize(x.into_deserializer())
+                .unwrap()
+                .value()
+        }
+
+        assert_eq!(value(1.5f64), 1.5);
+        assert_eq!(value(-3i64), -3.0);
+        assert_eq!(value(7u64), 7.0);
+        assert_eq!(value("2.25"), 2.25);
+
+        #[derive(Deserialize)]
+        struct Wallet {
+            gold: GoldData,
+            renown: CurrencyValue,
+            piety: CurrencyValue,
+        }
+
+        let data = b"gold={ value=133.5 } renown=12 piety={ currency=10.5 accumulated=100.0 }";
+        let wallet: Wallet = from_utf8_slice(data).unwrap();
+        assert_eq!(wallet.gold.value(), 133.5);
+        assert_eq!(wallet.gold.accumulated(), None);
+        assert_eq!(wallet.renown.value(), 12.0);
+        assert_eq!(wallet.piety.value(), 10.5);
+        assert_eq!(wallet.piety.accumulated(), Some(100.0));
+
+        let data = b"gold={ value=133.5 } renown=12 piety={ accumulated=4 }";
+        assert!(from_utf8_slice::<Wallet>(data).is_err());
+    }
+
+    #[test]
+    fn test_tyranny() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={ alive_data={ health=4.7 tyranny=35.5 dread=22.25 } }
+                16323={ alive_data={ health=5.2 } }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ruler = game.living[&16322].alive_data.as_ref().unwrap();
+        assert_eq!(ruler.tyranny, Some(35.5));
+        assert_eq!(ruler.dread, Some(22.25));
+        let other = game.living[&16323].alive_data.as_ref().unwrap();
+        assert_eq!(other.tyranny, None);
+    }
+
+    #[test]
+    fn test_active_casus_belli() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            casus_belli_manager={
+                active_casus_belli={
+                    41={
+                        type=de_jure_cb
+                        attacker=16322
+                        defender=9999
+                        target_title=1234
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let manager = game.casus_belli_manager.unwrap();
+        let cb = &manager.active_casus_belli[&41];
+        assert_eq!(cb.cb_type.as_deref(), Some("de_jure_cb"));
+        assert_eq!(cb.attacker, Some(16322));
+        assert_eq!(cb.defender, Some(9999));
+        assert_eq!(cb.target_title, Some(1234));
+    }
+
+    #[test]
+    fn test_council_tasks() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            council_task_manager={
+                council_tasks={
+                    88={
+                        councillor=16322
+                        type=task_collect_taxes
+                        progress=42.5
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let tasks = game.council_task_manager.unwrap().council_tasks;
+        let task = &tasks[&88];
+        assert_eq!(task.councillor, Some(16322));
+        assert_eq!(task.task_type.as_deref(), Some("task_collect_taxes"));
+        assert_eq!(task.progress, Some(42.5));
+    }
+
+    #[test]
+    fn test_accolades() {
+        let data = br#"
+            meta_data={ version="1.5.0" }
+            living={}
+            accolades={
+                accolades={
+                    3={
+                        name="The Bold Rider"
+                        knight=200
+                        owner=16322
+                        type=cavalry_leader_attribute
+                    }
+                    4={ knight=201 }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let accolades = game.accolades.unwrap().accolades;
+        let accolade = &accolades[&3];
+        assert_eq!(accolade.knight, Some(200));
+        assert_eq!(accolade.owner, Some(16322));
+        assert_eq!(accolade.name.as_deref(), Some("The Bold Rider"));
+        assert_eq!(
+            accolade.accolade_type.as_deref(),
+            Some("cavalry_leader_attribute")
+        );
+        assert_eq!(accolades[&4].name, None);
+    }
+
+    #[test]
+    fn test_holding_buildings() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            provinces={
+                2240={
+                    holding={
+                        type=castle_holding
+                        buildings={
+                            { type=curtain_walls_01 }
+                            { type=barracks_01 construction_progress=0.25 }
+                            { }
+                        }
+                    }
+                }
+                2241={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let provinces = game.provinces.unwrap();
+        let holding = provinces[&2240].holding.as_ref().unwrap();
+        assert_eq!(holding.holding_type.as_deref(), Some("castle_holding"));
+        let buildings = holding.buildings.as_ref().unwrap();
+        assert_eq!(
+            buildings[0].building_type.as_deref(),
+            Some("curtain_walls_01")
+        );
+        assert_eq!(buildings[0].construction_progress, None);
+        assert_eq!(buildings[1].construction_progress, Some(0.25));
+        assert_eq!(buildings[2].building_type, None);
+        assert!(provinces[&2241].holding.is_none());
+    }
+
+    #[test]
+    fn test_memory_participants() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            character_memory_manager={
+                database={
+                    77={
+                        type=murdered_relative
+                        owner=200
+                        creation_date=870.3.2
+                        participants={
+                            perpetrator=16322
+                            victim=201
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let memories = game.character_memory_manager.unwrap().database;
+        let memory = &memories[&77];
+        assert_eq!(memory.memory_type.as_deref(), Some("murdered_relative"));
+        assert_eq!(memory.creation_date, Some(Ck3Date::from_ymd(870, 3, 2)));
+        assert_eq!(
+            memory.participants,
+            vec![
+                MemoryParticipant {
+                    role: String::from("perpetrator"),
+                    character: 16322
+                },
+                MemoryParticipant {
+                    role: String::from("victim"),
+                    character: 201
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_great_projects() {
+        let data = br#"
+            meta_data={ version="1.13.0" }
+            living={}
+            great_projects={
+                great_projects={
+                    7={
+                        type=gp_grand_cathedral
+                        level=2
+                        location=2240
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let projects = game.great_projects.unwrap().great_projects;
+        let project = &projects[&7];
+        assert_eq!(project.project_type.as_deref(), Some("gp_grand_cathedral"));
+        assert_eq!(project.level, Some(2));
+        assert_eq!(project.location, Some(2240));
+    }
+
+    #[test]
+    fn test_stories() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            stories={
+                active={
+                    31={
+                        type=story_cycle_pet_dog
+                        owner=16322
+                        variables={
+                            dog_age=3
+                            dog_name=flag:dog_name_1
+                            is_good_dog=yes
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let stories = game.stories.unwrap().active;
+        let story = &stories[&31];
+        assert_eq!(story.story_type.as_deref(), Some("story_cycle_pet_dog"));
+        assert_eq!(story.owner, Some(16322));
+        assert_eq!(story.variables["dog_age"], VariableValue::Number(3.0));
+        assert_eq!(
+            story.variables["dog_name"],
+            VariableValue::Text(String::from("flag:dog_name_1"))
+        );
+        assert_eq!(story.variables["is_good_dog"], VariableValue::Bool(true));
+    }
+
+    #[test]
+    fn test_epidemics() {
+        let data = br#"
+            meta_data={ version="1.12.0" }
+            living={}
+            epidemics={
+                active_epidemics={
+                    5={
+                        type=bubonic_plague
+                        start_date=1347.10.1
+                        provinces={ 2240 2241 }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let epidemics = game.epidemics.unwrap().active_epidemics;
+        let epidemic = &epidemics[&5];
+        assert_eq!(epidemic.epidemic_type.as_deref(), Some("bubonic_plague"));
+        assert_eq!(epidemic.start_date, Some(Ck3Date::from_ymd(1347, 10, 1)));
+        assert_eq!(epidemic.provinces, vec![2240, 2241]);
+    }
+
+    #[test]
+    fn test_legends() {
+        let data = br#"
+            meta_data={ version="1.13.0" }
+            living={}
+            legends={
+                legends={
+                    12={
+                        type=heroic
+                        protagonist=16322
+                        owner=16322
+                        chapters={
+                            opening=legend_chapter_opening_heroic
+                            climax=legend_chapter_climax_heroic
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let legends = game.legends.unwrap().legends;
+        let legend = &legends[&12];
+        assert_eq!(legend.protagonist, Some(16322));
+        assert_eq!(legend.legend_type.as_deref(), Some("heroic"));
+        assert_eq!(
+            legend.chapters["opening"],
+            String::from("legend_chapter_opening_heroic")
+        );
+    }
+
+    #[test]
+    fn test_faiths() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            religion={
+                religions={ 3={ tag="christianity_religion" } }
+                faiths={
+                    12={ tag="catholic" religion=3 fervor=56.25 }
+                    13={ tag="orthodox" religion=3 }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let faiths = game.religion.unwrap().faiths;
+        let catholic = &faiths[&12];
+        assert_eq!(catholic.tag.as_deref(), Some("catholic"));
+        assert_eq!(catholic.religion, Some(3));
+        assert_eq!(catholic.fervor, Some(56.25));
+        assert_eq!(faiths[&13].fervor, None);
+    }
+
+    #[test]
+    fn test_dynasties() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            dynasties={
+                dynasty_house={
+                    1044={ name="dynn_Ivaring" dynasty=600 }
+                }
+                dynasties={
+                    600={
+                        name="dynn_Ivaring"
+                        prestige_total=1250.5
+                        perk=warfare_legacy_1
+                        perk=warfare_legacy_2
+                    }
+                    601={ name="dynn_Yngling" }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let dynasties = game.dynasties.unwrap().dynasties;
+        let ivaring = &dynasties[&600];
+        assert_eq!(ivaring.name.as_deref(), Some("dynn_Ivaring"));
+        assert_eq!(ivaring.prestige_total, Some(1250.5));
+        assert_eq!(ivaring.perks, vec!["warfare_legacy_1", "warfare_legacy_2"]);
+        assert_eq!(dynasties[&601].prestige_total, None);
+        assert!(dynasties[&601].perks.is_empty());
+    }
+
+    #[test]
+    fn test_title_treasury() {
+        let data = br#"
+            meta_data={ version="1.14.0" }
+            living={}
+            landed_titles={
+                landed_titles={
+                    7={ key="e_byzantium" treasury=65.536 }
+                    8={ key="k_norway" treasury={ value=32.768 } }
+                    9={ key="c_uppland" }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let titles = game.landed_titles.unwrap().landed_titles;
+        assert_eq!(titles[&7].treasury, Some(65.536));
+        assert_eq!(titles[&8].treasury, Some(32.768));
+        assert_eq!(titles[&9].treasury, None);
+    }
+
+    #[test]
+    fn test_title_history() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            landed_titles={
+                landed_titles={
+                    7={
+                        key="k_norway"
+                        history={
+                            867.1.1=16322
+                            873.6.2={ type=conquest holder=200 }
+                            900.2.1=none
+                        }
+                    }
+                    9={ key="c_uppland" }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let titles = game.landed_titles.unwrap().landed_titles;
+        let history = titles[&7].history.as_ref().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].date, Ck3Date::from_ymd(867, 1, 1));
+        assert_eq!(history[0].holder, Some(16322));
+        assert_eq!(history[1].date, Ck3Date::from_ymd(873, 6, 2));
+        assert_eq!(history[1].holder, Some(200));
+        assert_eq!(history[1].event_type.as_deref(), Some("conquest"));
+        assert_eq!(history[2].holder, None);
+        assert_eq!(titles[&9].history, None);
+    }
+
+    #[test]
+    fn test_diarchy() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    diarchy={
+                        type=regency
+                        diarch=200
+                        mandate=mandate_guard_realm
+                    }
+                }
+                200={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let diarchy = game.living[&16322].diarchy.as_ref().unwrap();
+        assert_eq!(diarchy.diarch, Some(200));
+        assert_eq!(diarchy.diarchy_type.as_deref(), Some("regency"));
+        assert_eq!(diarchy.mandate.as_deref(), Some("mandate_guard_realm"));
+        assert!(game.living[&200].diarchy.is_none());
+    }
+
+    #[test]
+    fn test_ai_strategies() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    ai_strategies={
+                        ai_war_strategy=200
+                        ai_marriage_strategy=3
+                    }
+                }
+                200={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let strategies = game.living[&16322].ai_strategies.as_ref().unwrap();
+        assert_eq!(
+            strategies,
+            &vec![
+                AiStrategy {
+                    strategy_type: String::from("ai_war_strategy"),
+                    target: 200,
+                },
+                AiStrategy {
+                    strategy_type: String::from("ai_marriage_strategy"),
+                    target: 3,
+                },
+            ]
+        );
+        assert!(game.living[&200].ai_strategies.is_none());
+    }
+
+    #[test]
+    fn test_domicile() {
+        let data = br#"
+            meta_data={ version="1.13.0" }
+            living={
+                16322={
+                    domicile={
+                        location=2163
+                        type=estate
+                        buildings={
+                            { key=estate_main_building_01 }
+                            { key=estate_vineyard_01 }
+                        }
+                    }
+                }
+                200={ }
+                300={ domicile=12 }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let domicile = game.living[&16322].domicile.as_ref().unwrap();
+        assert_eq!(domicile.location, Some(2163));
+        assert_eq!(domicile.domicile_type.as_deref(), Some("estate"));
+        assert_eq!(domicile.buildings.len(), 2);
+        assert_eq!(
+            domicile.buildings[1].key.as_deref(),
+            Some("estate_vineyard_01")
+        );
+        assert!(game.living[&200].domicile.is_none());
+        assert!(game.living[&300].domicile.is_none());
+    }
+
+    #[test]
+    fn test_men_at_arms() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    men_at_arms={
+                        { type=huscarl size=100 }
+                        { type=bowmen size=57 }
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let regiments = game.living[&16322].men_at_arms.as_ref().unwrap();
+        assert_eq!(regiments.len(), 2);
+        assert_eq!(regiments[0].maa_type.as_deref(), Some("huscarl"));
+        assert_eq!(regiments[0].size, Some(100));
+        assert_eq!(regiments[1].size, Some(57));
+    }
+
+    #[test]
+    fn test_court_positions() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={}
+            court_positions={
+                16322={
+                    positions={
+                        court_physician_court_position=200
+                        bodyguard_court_position=201
+                    }
+                }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let courts = game.court_positions.unwrap();
+        let positions = &courts[&16322].positions;
+        assert_eq!(positions["court_physician_court_position"], 200);
+        assert_eq!(positions.get("master_of_hunt_court_position"), None);
+    }
+
+    #[test]
+    fn test_character_record() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    first_name="Ivar"
+                    birth=845.1.1
+                    dynasty_house=1044
+                    culture=12
+                    faith=4
+                }
+                200={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ivar = &game.living[&16322];
+        assert_eq!(ivar.first_name.as_deref(), Some("Ivar"));
+        assert_eq!(ivar.birth, Some(Ck3Date::from_ymd(845, 1, 1)));
+        assert_eq!(ivar.dynasty_house, Some(1044));
+        assert_eq!(ivar.culture, Some(12));
+        assert_eq!(ivar.faith, Some(4));
+        assert_eq!(game.living[&200].dynasty_house, None);
+    }
+
+    #[test]
+    fn test_dna() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    first_name="Ivar"
+                    dna="AAcACQBfAF8AJwAnAHwAfAB/AH8="
+                }
+                200={ first_name="Halfdan" }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let dna = game.living[&16322].dna.as_deref();
+        assert_eq!(dna, Some("AAcACQBfAF8AJwAnAHwAfAB/AH8="));
+        assert_eq!(game.living[&200].dna, None);
+    }
+
+    #[test]
+    fn test_nickname() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    first_name="Ivar"
+                    nickname=nick_the_boneless
+                }
+                200={ first_name="Halfdan" }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ivar = &game.living[&16322];
+        assert_eq!(ivar.nickname.as_deref(), Some("nick_the_boneless"));
+        assert_eq!(game.living[&200].nickname, None);
+    }
+
+    #[test]
+    fn test_faith_conversion() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    faith=12
+                    faith_conversion={ faith=4 progress=37.5 }
+                }
+                200={ faith=12 }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        let ivar = &game.living[&16322];
+        assert_eq!(ivar.faith, Some(12));
+        let conversion = ivar.faith_conversion.as_ref().unwrap();
+        assert_eq!(conversion.faith, Some(4));
+        assert_eq!(conversion.progress, Some(37.5));
+        assert!(game.living[&200].faith_conversion.is_none());
+    }
+
+    #[test]
+    fn test_perks() {
+        let data = br#"
+            meta_data={ version="1.9.0" }
+            living={
+                16322={
+                    alive_data={
+                        health=4.7
+                        perk=family_man_perk
+                        perk=strategist_perk
+                    }
+                }
+                200={ alive_data={ perk=august_perk } }
+                201={ alive_data={ health=5.0 } }
+                202={ }
+            }
+        "#;
+
+        let game: Gamestate = from_utf8_slice(data).unwrap();
+        assert_eq!(
+            game.living[&16322].perks(),
+            &[
+                String::from("family_man_perk"),
+                String::from("strategist_perk")
+            ]
+        );
+        assert_eq!(game.living[&200].perks(), &[String::from("august_perk")]);
+        assert!(game.living[&201].perks().is_empty());
+        assert!(game.living[&202].perks().is_empty());
+    }
+
+    #[test]
+    fn test_i64_binary_date() {
+        use crate::testing::{binary_save, id, resolver};
+        use jomini::binary::Token;
+
+        let birth = i64::from(Ck3Date::from_ymd(867, 1, 1).to_binary());
+        let data = binary_save(&[
+            id("living"),
+            Token::Equal,
+            Token::Open,
+            Token::U32(1),
+            Token::Equal,
+            Token::Open,
+            id("birth"),
+            Token::Equal,
+            Token::I64(birth),
+            Token::Close,
+            Token::Close,
+        ]);
+        let file = crate::Ck3File::from_slice(&data).unwrap();
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink).unwrap();
+        let resolver = resolver();
+        let game: Gamestate = parsed.deserializer(&resolver).deserialize().unwrap();
+        assert_eq!(game.living[&1].birth, Some(Ck3Date::from_ymd(867, 1, 1)));
+    }
 }
@@ -1,24 +1,57 @@
+use crate::Ck3Date;
 use serde::Deserialize;
 use std::borrow::Cow;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct HeaderOwned {
     pub meta_data: MetadataOwned,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct HeaderBorrowed<'a> {
     #[serde(borrow)]
     pub meta_data: MetadataBorrowed<'a>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct MetadataOwned {
     pub version: String,
+    /// The start scenario (eg: `bm_867_ivar`). Absent for custom starts and
+    /// saves that predate it.
+    #[serde(default)]
+    pub bookmark: Option<String>,
+    /// The in-game date of the save
+    #[serde(default)]
+    pub meta_date: Option<Ck3Date>,
+    /// The real world time played, stored as a date
+    #[serde(default)]
+    pub meta_real_date: Option<Ck3Date>,
+    /// The names of the mods enabled for the save
+    #[serde(default)]
+    pub mods: Option<Vec<String>>,
+    /// The names of the DLCs enabled for the save
+    #[serde(default)]
+    pub dlcs: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct MetadataBorrowed<'a> {
     #[serde(borrow)]
     pub version: Cow<'a, str>,
+    /// The start scenario (eg: `bm_867_ivar`). Absent for custom starts and
+    /// saves that predate it.
+    #[serde(borrow, default)]
+    pub bookmark: Option<Cow<'a, str>>,
+    /// The in-game date of the save
+    #[serde(default)]
+    pub meta_date: Option<Ck3Date>,
+    /// The real world time played, stored as a date
+    #[serde(default)]
+    pub meta_real_date: Option<Ck3Date>,
+    /// The names of the mods enabled for the save
+    #[serde(borrow, default)]
+    pub mods: Option<Vec<Cow<'a, str>>>,
+    /// The names of the DLCs enabled for the save
+    #[serde(borrow, default)]
+    pub dlcs: Option<Vec<Cow<'a, str>>>,
 }
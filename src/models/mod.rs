@@ -1,5 +1,7 @@
 mod gamestate;
 mod header;
+mod save;
 
 pub use gamestate::*;
 pub use header::*;
+pub use save::*;
@@ -0,0 +1,52 @@
+use crate::{
+    models::{Gamestate, HeaderOwned},
+    Ck3Error, Ck3File,
+};
+use jomini::binary::TokenResolver;
+
+/// The header and gamestate of a save deserialized together
+#[derive(Debug)]
+pub struct Ck3Save {
+    pub header: HeaderOwned,
+    pub gamestate: Gamestate,
+}
+
+impl Ck3Save {
+    /// Parses the file and deserializes both the header and the gamestate
+    ///
+    /// Works for all encodings. The header is deserialized from the parsed
+    /// gamestate so that saves with an inaccurate metadata length in the save
+    /// header are still supported.
+    pub fn from_file<R>(file: &Ck3File, resolver: &R) -> Result<Self, Ck3Error>
+    where
+        R: TokenResolver,
+    {
+        let mut zip_sink = Vec::new();
+        let parsed = file.parse(&mut zip_sink)?;
+        let deserializer = parsed.deserializer(resolver);
+        let header = deserializer.deserialize()?;
+        let gamestate = deserializer.deserialize()?;
+        Ok(Ck3Save { header, gamestate })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{binary_save, living, resolver, zip};
+    use crate::Encoding;
+
+    #[test]
+    fn test_ck3_save_from_binary_zip() {
+        let data = binary_save(&living());
+        let mut save = b"SAV01030000000000000000\n".to_vec();
+        save.extend_from_slice(&zip(&data[24..]));
+        let file = Ck3File::from_slice(&save).unwrap();
+        assert_eq!(file.encoding(), Encoding::BinaryZip);
+
+        let save = Ck3Save::from_file(&file, &resolver()).unwrap();
+        assert_eq!(save.header.meta_data.version, "1.0.2");
+        assert_eq!(save.gamestate.meta_data.version, "1.0.2");
+        assert_eq!(save.gamestate.living.len(), 2);
+    }
+}
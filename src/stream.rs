@@ -0,0 +1,116 @@
+use crate::{file::Ck3Meta, Ck3Error, Ck3ErrorKind, Ck3File, SaveHeader};
+
+/// How much of a save a [`Ck3StreamParser`] has made available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StreamProgress {
+    /// Not enough bytes have arrived to decode the save header
+    Pending,
+
+    /// The save header is available
+    Header,
+
+    /// The save header and the inlined metadata section are available
+    Metadata,
+}
+
+/// Incrementally ingests a save as it arrives in chunks (eg: from a web
+/// upload) so that the header and metadata can be inspected before the
+/// gamestate has been received.
+///
+/// ```
+/// use ck3save::{Ck3StreamParser, StreamProgress};
+///
+/// let mut parser = Ck3StreamParser::new();
+/// assert_eq!(parser.push_bytes(b"SAV0100a40f789f0000")?, StreamProgress::Pending);
+/// assert_eq!(parser.push_bytes(b"0000\n")?, StreamProgress::Header);
+/// assert!(parser.header().is_some());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Metadata is only available early when the save inlines it ahead of the
+/// gamestate. Once all bytes have been pushed, the entire save is accessible
+/// with [`Ck3StreamParser::file`].
+#[derive(Debug, Default)]
+pub struct Ck3StreamParser {
+    data: Vec<u8>,
+    header: Option<SaveHeader>,
+}
+
+impl Ck3StreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the next chunk of the save and report the progress made so far
+    ///
+    /// Errors as soon as the received bytes can't be the start of a save.
+    pub fn push_bytes(&mut self, data: &[u8]) -> Result<StreamProgress, Ck3Error> {
+        self.data.extend_from_slice(data);
+
+        if self.header.is_none() {
+            let prefix = self.data.len().min(3);
+            if self.data[..prefix] != b"SAV"[..prefix] {
+                return Err(Ck3ErrorKind::InvalidHeader.into());
+            }
+
+            let complete = match self.data.get(23) {
+                Some(b'\r') => self.data.len() >= 25,
+                Some(_) => true,
+                None => false,
+            };
+
+            if complete {
+                self.header = Some(SaveHeader::from_slice(&self.data)?);
+            }
+        }
+
+        Ok(self.progress())
+    }
+
+    /// The progress made with the bytes received so far
+    pub fn progress(&self) -> StreamProgress {
+        match &self.header {
+            None => StreamProgress::Pending,
+            Some(_) if self.metadata().is_some() => StreamProgress::Metadata,
+            Some(_) => StreamProgress::Header,
+        }
+    }
+
+    /// The save header, once enough bytes have been received
+    pub fn header(&self) -> Option<&SaveHeader> {
+        self.header.as_ref()
+    }
+
+    /// The inlined metadata section, once it has been received in full
+    pub fn meta(&self) -> Option<Ck3Meta<'_>> {
+        let header = self.header.clone()?;
+        self.metadata().map(|data| Ck3Meta::inlined(data, header))
+    }
+
+    fn metadata(&self) -> Option<&[u8]> {
+        let header = self.header.as_ref()?;
+        let len = usize::try_from(header.metadata_len()).ok()?;
+        if len == 0 {
+            return None;
+        }
+
+        let start = header.header_len();
+        self.data.get(start..start.checked_add(len)?)
+    }
+
+    /// The bytes received so far
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Interpret all the received bytes as a save. Intended to be called
+    /// after the final chunk has been pushed.
+    pub fn file(&self) -> Result<Ck3File<'_>, Ck3Error> {
+        Ck3File::from_slice(&self.data)
+    }
+
+    /// Consume the parser and return the received bytes
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
@@ -0,0 +1,96 @@
+//! Helpers for unit tests that need a small binary save
+
+use jomini::{binary::Token, Scalar};
+use std::collections::HashMap;
+
+const NAMES: &[&str] = &[
+    "meta_data",
+    "save_game_version",
+    "version",
+    "living",
+    "first_name",
+    "alive_data",
+    "gold",
+    "birth",
+    "dynasty_house",
+    "inspirations_manager",
+    "inspirations",
+    "owner",
+    "name_list",
+    "liege",
+    "traits",
+    "prestige",
+    "ironman",
+];
+
+pub(crate) fn id(name: &str) -> Token<'static> {
+    let index = NAMES.iter().position(|x| *x == name).unwrap();
+    Token::Id(0x2000 + index as u16)
+}
+
+pub(crate) fn resolver() -> HashMap<u16, &'static str> {
+    NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (0x2000 + i as u16, *name))
+        .collect()
+}
+
+/// Creates an uncompressed binary save out of the given gamestate tokens
+pub(crate) fn binary_save(gamestate: &[Token]) -> Vec<u8> {
+    let mut tokens = vec![
+        id("meta_data"),
+        Token::Equal,
+        Token::Open,
+        id("save_game_version"),
+        Token::Equal,
+        Token::I32(3),
+        id("version"),
+        Token::Equal,
+        Token::Quoted(Scalar::new(b"1.0.2")),
+        Token::Close,
+    ];
+    tokens.extend_from_slice(gamestate);
+
+    let mut out = b"SAV01010000000000000000\n".to_vec();
+    for token in &tokens {
+        token.write(&mut out).unwrap();
+    }
+    out
+}
+
+/// Two living characters: 1 (Ivar) and 2 (Halfdan)
+pub(crate) fn living() -> Vec<Token<'static>> {
+    vec![
+        id("living"),
+        Token::Equal,
+        Token::Open,
+        Token::U32(1),
+        Token::Equal,
+        Token::Open,
+        id("first_name"),
+        Token::Equal,
+        Token::Quoted(Scalar::new(b"Ivar")),
+        Token::Close,
+        Token::U32(2),
+        Token::Equal,
+        Token::Open,
+        id("first_name"),
+        Token::Equal,
+        Token::Quoted(Scalar::new(b"Halfdan")),
+        Token::Close,
+        Token::Close,
+    ]
+}
+
+/// Wraps the given (uncompressed) gamestate in a zip archive
+pub(crate) fn zip(data: &[u8]) -> Vec<u8> {
+    let mut zipped = Vec::new();
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut zipped));
+    let options = zip::write::FileOptions::default();
+    writer.start_file("gamestate", options).unwrap();
+    std::io::Write::write_all(&mut writer, data).unwrap();
+    writer.finish().unwrap();
+    drop(writer);
+    zipped
+}
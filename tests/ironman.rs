@@ -1,9 +1,13 @@
 use ck3save::{
     models::{Gamestate, HeaderBorrowed, HeaderOwned},
-    BasicTokenResolver, Ck3File, Encoding, FailedResolveStrategy,
+    BasicTokenResolver, Ck3ErrorKind, Ck3File, CompressionKind, Encoding, FailedResolveStrategy,
+    GoldRepr, MeltOptions,
 };
 use jomini::binary::TokenResolver;
-use std::{io::Cursor, sync::LazyLock};
+use std::{
+    io::{Cursor, Read},
+    sync::LazyLock,
+};
 
 mod utils;
 
@@ -44,6 +48,17 @@ fn test_ck3_binary_header() {
     assert_eq!(header.meta_data.version, String::from("1.0.2"));
 }
 
+#[test]
+fn test_ck3_deserialize_binary_header() {
+    skip_if_no_tokens!();
+    let data = include_bytes!("fixtures/header.bin");
+    let header = ck3save::deserialize_header(&data[..], &*TOKENS).unwrap();
+    assert_eq!(header.meta_data.version, String::from("1.0.2"));
+
+    let err = ck3save::deserialize_header(&data[24..], &*TOKENS).unwrap_err();
+    assert!(matches!(err.kind(), Ck3ErrorKind::InvalidHeader));
+}
+
 #[test]
 fn test_ck3_binary_header_borrowed() {
     skip_if_no_tokens!();
@@ -96,7 +111,7 @@ fn test_ck3_binary_compressed_header() {
     let meta = file.meta();
     let mut out = Cursor::new(Vec::new());
     meta.melter().melt(&mut out, &*TOKENS).unwrap();
-    memchr::memmem::find(&out.get_ref(), b"meta_real_date=124.11.1").unwrap();
+    memchr::memmem::find(out.get_ref(), b"meta_real_date=124.11.1").unwrap();
 }
 
 #[test]
@@ -122,12 +137,23 @@ fn test_ck3_binary_autosave() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut out = Cursor::new(Vec::new());
     file.melter().melt(&mut out, &*TOKENS)?;
-    memchr::memmem::find(&out.get_ref(), b"gold=0.044").unwrap();
-    memchr::memmem::find(&out.get_ref(), b"gold=4.647").unwrap();
+    memchr::memmem::find(out.get_ref(), b"gold=0.044").unwrap();
+    memchr::memmem::find(out.get_ref(), b"gold=4.647").unwrap();
 
     Ok(())
 }
 
+#[test]
+fn test_ck3_binary_autosave_gamestate_range() {
+    skip_if_no_tokens!();
+    let data = request_zip("autosave.zip");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    assert_eq!(
+        file.gamestate_range(),
+        file.header().header_len()..data.len()
+    );
+}
+
 #[test]
 fn test_ck3_binary_save_tokens() -> Result<(), Box<dyn std::error::Error>> {
     if TOKENS.is_empty() {
@@ -152,7 +178,7 @@ fn test_roundtrip_header_melt() {
     let mut out = Cursor::new(Vec::new());
     header.melter().melt(&mut out, &*TOKENS).unwrap();
 
-    let file = Ck3File::from_slice(&out.get_ref()).unwrap();
+    let file = Ck3File::from_slice(out.get_ref()).unwrap();
     let meta = file.meta();
     let mut zip_sink = Vec::new();
     let header = meta.parse(&mut zip_sink).unwrap();
@@ -192,7 +218,7 @@ fn test_ck3_binary_save_patch_1_3() -> Result<(), Box<dyn std::error::Error>> {
     let mut out = Cursor::new(Vec::new());
     file.melter().melt(&mut out, &*TOKENS)?;
 
-    let file = Ck3File::from_slice(&out.get_ref())?;
+    let file = Ck3File::from_slice(out.get_ref())?;
     let mut zip_sink = Vec::new();
     let parsed_file = file.parse(&mut zip_sink)?;
 
@@ -216,7 +242,7 @@ fn test_ck3_1_0_3_old_cloud_and_local_tokens() -> Result<(), Box<dyn std::error:
     let mut out = Cursor::new(Vec::new());
     file.melter().melt(&mut out, &*TOKENS)?;
 
-    let file = Ck3File::from_slice(&out.get_ref())?;
+    let file = Ck3File::from_slice(out.get_ref())?;
     let mut zip_sink = Vec::new();
     let parsed_file = file.parse(&mut zip_sink)?;
     assert_eq!(file.encoding(), Encoding::Text);
@@ -263,11 +289,62 @@ fn decode_and_melt_gold_correctly() -> Result<(), Box<dyn std::error::Error>> {
     let mut out = Cursor::new(Vec::new());
     file.melter().melt(&mut out, &*TOKENS)?;
 
-    memchr::memmem::find(&out.get_ref(), b"gold=133.04397").unwrap();
-    memchr::memmem::find(&out.get_ref(), b"vassal_power_value=200").unwrap();
+    memchr::memmem::find(out.get_ref(), b"gold=133.04397").unwrap();
+    memchr::memmem::find(out.get_ref(), b"vassal_power_value=200").unwrap();
     Ok(())
 }
 
+#[test]
+fn melt_deserialize_stable_with_gold() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    ck3save::assert_melt_deserialize_stable(&file, &*TOKENS);
+}
+
+#[test]
+fn decode_character_traits() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let save: Gamestate = parsed_file.deserializer(&*TOKENS).deserialize().unwrap();
+
+    let traits = save.living[&16322].traits.as_deref().unwrap();
+    assert!(!traits.is_empty());
+    assert!(traits.iter().all(|x| save.trait_name(*x).is_some()));
+}
+
+#[test]
+fn decode_predates_domiciles() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let save: Gamestate = parsed_file.deserializer(&*TOKENS).deserialize().unwrap();
+    assert!(save.living.values().all(|x| x.domicile.is_none()));
+}
+
+#[test]
+fn decode_ai_strategies() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let save: Gamestate = parsed_file.deserializer(&*TOKENS).deserialize().unwrap();
+    let strategies = save
+        .living
+        .values()
+        .filter_map(|x| x.ai_strategies.as_ref())
+        .flatten()
+        .collect::<Vec<_>>();
+    assert!(!strategies.is_empty());
+    assert!(strategies.iter().all(|x| !x.strategy_type.is_empty()));
+}
+
 #[test]
 fn parse_patch16() -> Result<(), Box<dyn std::error::Error>> {
     if TOKENS.is_empty() {
@@ -298,7 +375,7 @@ fn melt_patch14() -> Result<(), Box<dyn std::error::Error>> {
     file.melter().melt(&mut out, &*TOKENS)?;
 
     assert!(
-        eq(&out.get_ref(), &expected),
+        eq(out.get_ref(), &expected),
         "patch 1.4 did not melt currently"
     );
     Ok(())
@@ -316,12 +393,134 @@ fn melt_patch15() -> Result<(), Box<dyn std::error::Error>> {
     file.melter().melt(&mut out, &*TOKENS)?;
 
     assert!(
-        eq(&out.get_ref(), &expected),
+        eq(out.get_ref(), &expected),
         "patch 1.5 did not melt currently"
     );
     Ok(())
 }
 
+#[test]
+fn test_melt_lines_matches_melt() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let (expected, _) = file.melt_to_vec(&*TOKENS, MeltOptions::new()).unwrap();
+    let lines = file
+        .melt_lines(&*TOKENS, MeltOptions::new())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!((lines.join("\n") + "\n").as_bytes(), expected.as_slice());
+
+    let size = file.melted_size(&*TOKENS, MeltOptions::new()).unwrap();
+    assert_eq!(size, expected.len() as u64);
+}
+
+#[test]
+fn test_melt_compressed_matches_melt() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let (expected, _) = file.melt_to_vec(&*TOKENS, MeltOptions::new()).unwrap();
+
+    let mut out = Vec::new();
+    ck3save::melt_compressed(
+        &file,
+        &*TOKENS,
+        MeltOptions::new(),
+        &mut out,
+        CompressionKind::Gzip,
+    )
+    .unwrap();
+
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(out.as_slice())
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert!(eq(&decoded, &expected));
+}
+
+#[test]
+fn test_fingerprint_matches_melted() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let (melted, _) = file.melt_to_vec(&*TOKENS, MeltOptions::new()).unwrap();
+    let melted_file = Ck3File::from_slice(&melted).unwrap();
+    assert_eq!(
+        file.fingerprint(&*TOKENS).unwrap(),
+        melted_file.fingerprint(&*TOKENS).unwrap()
+    );
+    assert_eq!(
+        file.gamestate_hash(&*TOKENS).unwrap(),
+        melted_file.gamestate_hash(&*TOKENS).unwrap()
+    );
+}
+
+#[test]
+fn test_statistics_matches_gamestate() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let save: Gamestate = parsed_file.deserializer(&*TOKENS).deserialize().unwrap();
+
+    assert_eq!(file.gold_representation(&*TOKENS).unwrap(), GoldRepr::Float);
+
+    let stats = file.statistics(&*TOKENS).unwrap();
+    assert_eq!(stats.living_characters, save.living.len());
+    let gold: f64 = save
+        .living
+        .values()
+        .filter_map(|x| x.alive_data.as_ref().and_then(|x| x.gold))
+        .sum();
+    assert!(
+        (stats.total_gold - gold).abs() < 0.01,
+        "{} {}",
+        stats.total_gold,
+        gold
+    );
+}
+
+#[test]
+fn test_melt_include_characters() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let options = MeltOptions::new().include_characters([16322].into_iter().collect());
+    let (out, _) = file.melt_to_vec(&*TOKENS, options).unwrap();
+
+    let file = Ck3File::from_slice(&out).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let save: Gamestate = parsed_file.deserializer(&*TOKENS).deserialize().unwrap();
+    assert_eq!(save.living.len(), 1);
+    assert_eq!(
+        save.living[&16322].alive_data.as_ref().and_then(|x| x.gold),
+        Some(133.04397)
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_melt_key_filter() {
+    skip_if_no_tokens!();
+    let data = utils::request("ck3-1.3.1.ck3");
+    let file = Ck3File::from_slice(&data).unwrap();
+    let regex = regex::Regex::new("^gold$").unwrap();
+    let options = MeltOptions::new().key_filter(regex);
+    let lines = file
+        .melt_lines(&*TOKENS, options)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(lines
+        .iter()
+        .all(|x| x.split('=').next().unwrap().ends_with(".gold")));
+    assert!(lines
+        .iter()
+        .any(|x| x == "living.16322.alive_data.gold=133.04397"));
+}
+
 fn eq(a: &[u8], b: &[u8]) -> bool {
     for (ai, bi) in a.iter().zip(b.iter()) {
         if ai != bi {
@@ -1,6 +1,6 @@
 use ck3save::{
     models::{Gamestate, HeaderBorrowed, HeaderOwned},
-    Ck3File, Encoding,
+    Ck3ErrorKind, Ck3File, Encoding,
 };
 use std::collections::HashMap;
 mod utils;
@@ -18,6 +18,79 @@ fn test_ck3_text_header() {
         .deserialize()
         .unwrap();
     assert_eq!(header.meta_data.version, String::from("1.0.2"));
+    assert_eq!(header.meta_data.bookmark, None);
+    let date = header.meta_data.meta_date;
+    assert_eq!(date, Some(ck3save::Ck3Date::from_ymd(867, 1, 1)));
+    let dlcs = header.meta_data.dlcs.unwrap();
+    assert_eq!(
+        dlcs,
+        vec![String::from("Garments of the Holy Roman Empire")]
+    );
+    assert_eq!(header.meta_data.mods, None);
+}
+
+#[test]
+fn test_ck3_deserialize_text_header() {
+    let data = include_bytes!("fixtures/header.txt");
+    let resolver = HashMap::<u16, &str>::new();
+    let header = ck3save::deserialize_header(&data[..], &resolver).unwrap();
+    assert_eq!(header.meta_data.version, String::from("1.0.2"));
+
+    let err = ck3save::deserialize_header(&data[24..], &resolver).unwrap_err();
+    assert!(matches!(err.kind(), Ck3ErrorKind::InvalidHeader));
+}
+
+#[test]
+fn test_ck3_header_bookmark() {
+    let data = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.9.0\" bookmark=\"bm_867_ivar\" }\nliving={ }\n";
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed = file.parse(&mut zip_sink).unwrap();
+    let resolver = HashMap::<u16, &str>::new();
+    let header: HeaderBorrowed = parsed.deserializer(&resolver).deserialize().unwrap();
+    assert_eq!(header.meta_data.bookmark.as_deref(), Some("bm_867_ivar"));
+}
+
+#[test]
+fn test_ck3_header_line_variants() {
+    let metadata = "meta_data={ version=\"1.9.0\" }\r\n";
+    let save = format!("SAV0100A40F789F{:08X}\r\n{}", metadata.len(), metadata);
+    let file = Ck3File::from_slice(save.as_bytes()).unwrap();
+    assert_eq!(file.encoding(), Encoding::Text);
+    assert_eq!(file.header().header_len(), 25);
+
+    let meta = file.meta();
+    let mut zip_sink = Vec::new();
+    let header = meta.parse(&mut zip_sink).unwrap();
+    let header: HeaderOwned = header
+        .deserializer(&HashMap::<u16, &str>::new())
+        .deserialize()
+        .unwrap();
+    assert_eq!(header.meta_data.version, String::from("1.9.0"));
+}
+
+#[test]
+fn test_ck3_save_id_line() {
+    let data = include_bytes!("fixtures/header.txt");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    assert_eq!(file.save_id_line(), b"SAV0102a40f789f000067c4\n");
+
+    let save = b"SAV0100A40F789F00000000\r\nmeta_data={ }\r\n";
+    let file = Ck3File::from_slice(&save[..]).unwrap();
+    assert_eq!(file.save_id_line(), b"SAV0100A40F789F00000000\r\n");
+}
+
+#[test]
+fn test_ck3_header_mods() {
+    let data = b"SAV0100a40f789f00000000\nmeta_data={ version=\"1.9.0\" mods={ \"Community Flavor Pack\" \"Nameplay\" } dlcs={ } }\nliving={ }\n";
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed = file.parse(&mut zip_sink).unwrap();
+    let resolver = HashMap::<u16, &str>::new();
+    let header: HeaderBorrowed = parsed.deserializer(&resolver).deserialize().unwrap();
+    let mods = header.meta_data.mods.unwrap();
+    assert!(mods.iter().any(|x| x == "Community Flavor Pack"));
+    assert_eq!(header.meta_data.dlcs, Some(Vec::new()));
 }
 
 #[test]
@@ -47,3 +120,210 @@ fn test_ck3_text_save() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(game.meta_data.version, String::from("1.0.2"));
     Ok(())
 }
+
+/// Deserializes the plaintext 1.0.2 sample save
+fn text_gamestate() -> Gamestate {
+    let data = utils::request("Jarl_Ivar_of_the_Isles_867_01_01.ck3");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    parsed_file
+        .deserializer(&HashMap::<u16, &str>::new())
+        .deserialize()
+        .unwrap()
+}
+
+#[test]
+fn test_ck3_text_save_wars() {
+    let game = text_gamestate();
+    let wars = game.wars.unwrap();
+    assert!(!wars.active_wars.is_empty());
+    for war in wars.active_wars.values() {
+        assert!(war.primary_attacker().is_some());
+        assert!(war.primary_defender().is_some());
+        assert!(war.casus_belli.is_some());
+    }
+}
+
+#[test]
+fn test_ck3_text_save_pending_interactions() {
+    let game = text_gamestate();
+    for interaction in game.pending_character_interactions.iter().flatten() {
+        assert!(!interaction.interaction.is_empty());
+        assert!(interaction.actor.is_some());
+        assert!(interaction.recipient.is_some());
+    }
+}
+
+#[test]
+fn test_ck3_text_save_county_manager() {
+    let game = text_gamestate();
+    let counties = game.county_manager.unwrap().counties;
+    assert!(!counties.is_empty());
+    assert!(counties.values().any(|x| x.development.is_some()));
+}
+
+#[test]
+fn test_ck3_text_save_holy_orders() {
+    let game = text_gamestate();
+    for order in game.holy_orders.values() {
+        assert!(order.name.is_some());
+        assert!(order.leader.is_some());
+    }
+}
+
+#[test]
+fn test_ck3_text_save_succession_laws() {
+    let game = text_gamestate();
+    let titles = game.landed_titles.unwrap().landed_titles;
+    assert!(titles.values().any(|x| !x.succession_laws.is_empty()));
+    for law in titles.values().flat_map(|x| x.succession_laws.iter()) {
+        assert!(law.ends_with("_law"), "{}", law);
+    }
+}
+
+#[test]
+fn test_ck3_text_save_vassal_contracts() {
+    let game = text_gamestate();
+    let contracts = game.vassal_contracts.unwrap().active;
+    assert!(!contracts.is_empty());
+    assert!(contracts.values().any(|x| x.liege.is_some()));
+    assert!(contracts.values().any(|x| x.vassal_power_value.is_some()));
+}
+
+#[test]
+fn test_ck3_text_save_traits() {
+    let game = text_gamestate();
+    let traits = game
+        .living
+        .values()
+        .filter_map(|x| x.traits.as_deref())
+        .flatten()
+        .collect::<Vec<_>>();
+    assert!(!traits.is_empty());
+    assert!(traits.iter().all(|x| game.trait_name(**x).is_some()));
+}
+
+#[test]
+fn test_ck3_text_save_predates_domiciles() {
+    let game = text_gamestate();
+    assert!(game.living.values().all(|x| x.domicile.is_none()));
+}
+
+#[test]
+fn test_characters_to_csv() {
+    let data = utils::request("Jarl_Ivar_of_the_Isles_867_01_01.ck3");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    let resolver = HashMap::<u16, &str>::new();
+    let mut out = Vec::new();
+    ck3save::characters_to_csv(&file, &resolver, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "id,first_name,gold,health,culture,birth");
+
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let game: Gamestate = parsed_file.deserializer(&resolver).deserialize().unwrap();
+    assert_eq!(lines.len(), game.living.len() + 1);
+}
+
+#[test]
+fn test_ck3_text_save_statistics() {
+    let data = utils::request("Jarl_Ivar_of_the_Isles_867_01_01.ck3");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    let resolver = HashMap::<u16, &str>::new();
+    let mut zip_sink = Vec::new();
+    let parsed_file = file.parse(&mut zip_sink).unwrap();
+    let game: Gamestate = parsed_file.deserializer(&resolver).deserialize().unwrap();
+
+    let stats = file.statistics(&resolver).unwrap();
+    assert_eq!(stats.living_characters, game.living.len());
+    let gold: f64 = game
+        .living
+        .values()
+        .filter_map(|x| x.alive_data.as_ref().and_then(|x| x.gold))
+        .sum();
+    assert!(
+        (stats.total_gold - gold).abs() < 0.01,
+        "{} {}",
+        stats.total_gold,
+        gold
+    );
+}
+
+#[test]
+fn test_stream_parser_chunks() {
+    let metadata = b"meta_data={ version=\"1.9.0\" }\n";
+    let mut save = format!("SAV0100a40f789f{:08x}\n", metadata.len()).into_bytes();
+    save.extend_from_slice(metadata);
+    save.extend_from_slice(b"living={\n");
+    for id in 0..2000 {
+        save.extend_from_slice(format!("\t{}={{ first_name=\"Ivar\" }}\n", id).as_bytes());
+    }
+    save.extend_from_slice(b"}\n");
+    assert!(save.len() > 4096 * 4);
+
+    let mut parser = ck3save::Ck3StreamParser::new();
+    let mut chunks = save.chunks(4096);
+    let progress = parser.push_bytes(chunks.next().unwrap()).unwrap();
+    assert_eq!(progress, ck3save::StreamProgress::Metadata);
+    assert_eq!(
+        parser.header().unwrap().metadata_len(),
+        metadata.len() as u64
+    );
+
+    let meta = parser.meta().unwrap();
+    let mut zip_sink = Vec::new();
+    let header = meta.parse(&mut zip_sink).unwrap();
+    let resolver = HashMap::<u16, &str>::new();
+    let header: HeaderBorrowed = header.deserializer(&resolver).deserialize().unwrap();
+    assert_eq!(header.meta_data.version, "1.9.0");
+
+    for chunk in chunks {
+        parser.push_bytes(chunk).unwrap();
+    }
+
+    let file = parser.file().unwrap();
+    let mut zip_sink = Vec::new();
+    let parsed = file.parse(&mut zip_sink).unwrap();
+    let game: Gamestate = parsed.deserializer(&resolver).deserialize().unwrap();
+    assert_eq!(game.living.len(), 2000);
+}
+
+#[test]
+fn test_stream_parser_rejects_non_saves() {
+    let mut parser = ck3save::Ck3StreamParser::new();
+    assert!(parser.push_bytes(b"PK").is_err());
+}
+
+#[test]
+fn test_ck3_date_from_str() {
+    let date = "1066.9.15".parse::<ck3save::Ck3Date>().unwrap();
+    assert_eq!(date, ck3save::Ck3Date::from_ymd(1066, 9, 15));
+    assert!("1066.13.15".parse::<ck3save::Ck3Date>().is_err());
+}
+
+#[test]
+fn test_ck3_date_hash() {
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, RandomState};
+
+    let a = "1066.9.15".parse::<ck3save::Ck3Date>().unwrap();
+    let b = "1066.09.15".parse::<ck3save::Ck3Date>().unwrap();
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(a), state.hash_one(b));
+
+    let dates = [a, b].into_iter().collect::<HashSet<_>>();
+    assert_eq!(dates.len(), 1);
+}
+
+#[test]
+fn test_requires_resolver() {
+    let data = include_bytes!("fixtures/header.txt");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    assert!(!file.requires_resolver());
+
+    let data = include_bytes!("fixtures/header.bin");
+    let file = Ck3File::from_slice(&data[..]).unwrap();
+    assert!(file.requires_resolver());
+}